@@ -0,0 +1,67 @@
+use std::env;
+use std::fmt::Write;
+use std::fs;
+use std::path::Path;
+
+/// Embeds the trusted module hashes and the module signing key for module verification.
+///
+/// - `KERNEL_MODULE_ALLOWLIST`: Path to a file with one hex encoded SHA-256 hash per line,
+///   as written by `sha256sum`. Empty lines and lines starting with `#` are ignored.
+/// - `KERNEL_MODULE_PUBLIC_KEY`: The hex encoded Ed25519 public key,
+///   which module signatures are checked against.
+fn main() {
+    println!("cargo:rerun-if-env-changed=KERNEL_MODULE_ALLOWLIST");
+    println!("cargo:rerun-if-env-changed=KERNEL_MODULE_PUBLIC_KEY");
+
+    let allowlist = env::var("KERNEL_MODULE_ALLOWLIST")
+        .map(|path| {
+            println!("cargo:rerun-if-changed={path}");
+
+            fs::read_to_string(&path)
+                .unwrap_or_else(|err| panic!("Failed to read module allowlist '{path}': {err}"))
+        })
+        .unwrap_or_default();
+
+    let mut out = String::from("/// The SHA-256 hashes of all trusted modules.\n");
+    out.push_str("pub const ALLOWLIST: &[[u8; 32]] = &[\n");
+
+    for line in allowlist.lines().map(str::trim) {
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+
+        let hash = line.split_whitespace().next().unwrap();
+
+        writeln!(out, "    {:?},", parse_hex::<32>(hash)).unwrap();
+    }
+
+    out.push_str("];\n\n/// The public key trusted module signatures are checked against.\n");
+
+    match env::var("KERNEL_MODULE_PUBLIC_KEY") {
+        Ok(key) => writeln!(
+            out,
+            "pub const PUBLIC_KEY: Option<[u8; 32]> = Some({:?});",
+            parse_hex::<32>(key.trim())
+        ),
+        Err(_) => writeln!(out, "pub const PUBLIC_KEY: Option<[u8; 32]> = None;"),
+    }
+    .unwrap();
+
+    let out_dir = env::var("OUT_DIR").unwrap();
+
+    fs::write(Path::new(&out_dir).join("module_verify.rs"), out)
+        .expect("Failed to write module verification data");
+}
+
+fn parse_hex<const N: usize>(hex: &str) -> [u8; N] {
+    assert_eq!(hex.len(), N * 2, "Invalid length of hex string '{hex}'");
+
+    let mut bytes = [0; N];
+
+    for (i, byte) in bytes.iter_mut().enumerate() {
+        *byte = u8::from_str_radix(&hex[i * 2..i * 2 + 2], 16)
+            .unwrap_or_else(|_| panic!("Invalid hex string '{hex}'"));
+    }
+
+    bytes
+}
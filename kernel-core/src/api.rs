@@ -1,4 +1,5 @@
 use crate::info::KernelApiInfo;
+use crate::process::{Trap, UserContext};
 use crate::sync::init::InitData;
 use core::alloc::Layout;
 use time::{OffsetDateTime, UtcDateTime};
@@ -50,6 +51,11 @@ pub const fn time() -> TimeApi {
     kernel().time
 }
 
+/// Get the global [ProcessApi].
+pub const fn process() -> ProcessApi {
+    kernel().process
+}
+
 /// Disable interrupts on the system.
 pub fn disable_interrupts() {
     (kernel().disable_interrupts)();
@@ -105,6 +111,8 @@ pub struct KernelApi {
     pub memory: MemoryApi,
     /// The [TimeApi] for time reading.
     pub time: TimeApi,
+    /// The [ProcessApi] for running user programs.
+    pub process: ProcessApi,
 }
 
 /// Port API of the kernel.
@@ -195,6 +203,8 @@ pub struct MemoryApi {
     pub translate: unsafe fn(addr: usize) -> usize,
     /// Maps the given physical address to a virtual address.
     pub map_to: unsafe fn(addr: usize, writable: bool, cache: bool) -> usize,
+    /// Changes the access permissions of the mapped pages containing the given virtual range.
+    pub protect: unsafe fn(addr: usize, size: usize, writable: bool, executable: bool) -> bool,
 }
 
 impl MemoryApi {
@@ -248,6 +258,22 @@ impl MemoryApi {
     pub unsafe fn map_to(&self, addr: usize, writable: bool, cache: bool) -> usize {
         unsafe { (self.map_to)(addr, writable, cache) }
     }
+
+    /// Changes the access permissions of the mapped pages containing the given virtual range.
+    ///
+    /// Returns `false` if any page of the range is not mapped.
+    ///
+    /// # Safety
+    /// The range must not be accessed in a way the new permissions forbid afterward.
+    pub unsafe fn protect(
+        &self,
+        addr: usize,
+        size: usize,
+        writable: bool,
+        executable: bool,
+    ) -> bool {
+        unsafe { (self.protect)(addr, size, writable, executable) }
+    }
 }
 
 /// The time API for the kernel.
@@ -281,3 +307,107 @@ impl TimeApi {
         (self.set_offset)(hours, minutes, seconds)
     }
 }
+
+/// The process API of the kernel.
+///
+/// Responsible for managing user address spaces and switching into user mode.
+///
+/// Address spaces are identified by an architecture specific handle,
+/// e.g. the physical address of the top-level page table.
+#[derive(Copy, Clone)]
+pub struct ProcessApi {
+    /// Create a new, empty user address space.
+    pub create_space: fn() -> Option<usize>,
+    /// Destroy a user address space and free all memory mapped in it.
+    pub destroy_space: unsafe fn(space: usize),
+    /// Map zeroed memory at the given user address range.
+    pub map:
+        unsafe fn(space: usize, addr: usize, size: usize, writable: bool, executable: bool) -> bool,
+    /// Copy data from a user address space into the given buffer.
+    pub read: unsafe fn(space: usize, addr: usize, buf: &mut [u8]) -> bool,
+    /// Copy the given data into a user address space.
+    pub write: unsafe fn(space: usize, addr: usize, data: &[u8]) -> bool,
+    /// Run the given user context until it traps back into the kernel.
+    pub enter: unsafe fn(space: usize, context: &mut UserContext) -> Trap,
+    /// Get the number of timer ticks since boot.
+    pub ticks: fn() -> u64,
+    /// The duration of a single timer tick in milliseconds.
+    pub tick_millis: u64,
+}
+
+impl ProcessApi {
+    /// Create a new, empty user address space.
+    ///
+    /// Returns [None] if there is not enough memory left.
+    pub fn create_space(&self) -> Option<usize> {
+        (self.create_space)()
+    }
+
+    /// Destroy a user address space and free all memory mapped in it.
+    ///
+    /// # Safety
+    /// The address space must be valid and must not be used afterward.
+    pub unsafe fn destroy_space(&self, space: usize) {
+        unsafe { (self.destroy_space)(space) }
+    }
+
+    /// Map zeroed memory at the given user address range.
+    ///
+    /// Already mapped pages are kept, but receive the combined permissions.
+    /// Returns `false` if the range is invalid or there is not enough memory left.
+    ///
+    /// # Safety
+    /// The address space must be valid.
+    pub unsafe fn map(
+        &self,
+        space: usize,
+        addr: usize,
+        size: usize,
+        writable: bool,
+        executable: bool,
+    ) -> bool {
+        unsafe { (self.map)(space, addr, size, writable, executable) }
+    }
+
+    /// Copy data from a user address space into the given buffer.
+    ///
+    /// Returns `false` if any part of the range is not mapped.
+    ///
+    /// # Safety
+    /// The address space must be valid.
+    pub unsafe fn read(&self, space: usize, addr: usize, buf: &mut [u8]) -> bool {
+        unsafe { (self.read)(space, addr, buf) }
+    }
+
+    /// Copy the given data into a user address space.
+    ///
+    /// Returns `false` if any part of the range is not mapped.
+    ///
+    /// # Safety
+    /// The address space must be valid.
+    pub unsafe fn write(&self, space: usize, addr: usize, data: &[u8]) -> bool {
+        unsafe { (self.write)(space, addr, data) }
+    }
+
+    /// Run the given user context until it traps back into the kernel.
+    ///
+    /// The context is updated with the register state at the time of the trap.
+    ///
+    /// # Safety
+    /// The address space must be valid and contain the program of the context.
+    pub unsafe fn enter(&self, space: usize, context: &mut UserContext) -> Trap {
+        unsafe { (self.enter)(space, context) }
+    }
+
+    /// Get the number of timer ticks since boot.
+    ///
+    /// User programs are preempted on every tick.
+    pub fn ticks(&self) -> u64 {
+        (self.ticks)()
+    }
+
+    /// Get the duration of a single timer tick in milliseconds.
+    pub fn tick_millis(&self) -> u64 {
+        self.tick_millis
+    }
+}
@@ -0,0 +1,233 @@
+use crate::control::script::PROFILE_SEPARATOR;
+use crate::requests;
+use crate::sync::init::InitData;
+use crate::time::TimeZone;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use log::LevelFilter;
+
+/// Global [BootConfig] instance, parsed from the kernel command line.
+pub static BOOT_CONFIG: InitData<BootConfig> = InitData::uninit();
+
+static mut INIT: bool = false;
+
+/// Parse the options on the kernel command line into the global [BOOT_CONFIG],
+/// apply the log level and log the invalid options.
+///
+/// # Safety
+/// This must only be called once after the heap is initialized,
+/// before any global [BOOT_CONFIG] use.
+pub unsafe fn init() {
+    let (options, _) = split_cmdline(requests::cmdline());
+    let (config, errors) = BootConfig::parse(options);
+
+    for err in errors {
+        log::warn!("Ignoring kernel command line option: {err}.");
+    }
+
+    if let Some(level) = config.log {
+        log::set_max_level(level);
+    }
+
+    unsafe {
+        BOOT_CONFIG.init(config);
+
+        INIT = true;
+    }
+}
+
+/// Get if the boot config is initialized.
+pub fn is_init() -> bool {
+    unsafe { INIT }
+}
+
+/// Split the kernel command line into the options and the profile statements,
+/// which follow after [PROFILE_SEPARATOR].
+pub fn split_cmdline(cmdline: &str) -> (&str, Option<&str>) {
+    if let Some(statements) = cmdline
+        .strip_prefix(PROFILE_SEPARATOR)
+        .filter(|rest| rest.is_empty() || rest.starts_with(' '))
+    {
+        return ("", Some(statements));
+    }
+
+    match cmdline.split_once(&format!(" {PROFILE_SEPARATOR} ")) {
+        Some((options, statements)) => (options, Some(statements)),
+        None => (cmdline, None),
+    }
+}
+
+/// The kernel configuration, parsed from the options on the limine executable command line.
+///
+/// The options are whitespace separated `key=value` pairs before the profile statements,
+/// e.g. `log=debug kbd=us tz=CET -- alias ll='module list'`:
+/// - `log=<off|error|warn|info|debug|trace>` sets the maximum log level.
+/// - `kbd=<layout>` sets the keyboard layout, e.g. `us` or `de`.
+/// - `tz=<zone>` sets the time zone, e.g. `CET` or `+01:+00:+00`.
+/// - `console=<both|screen|serial>` selects where the logs are written.
+/// - `modules=<on|off>` enables or disables loading the kernel modules during boot.
+/// - `autorun=<off|path>` disables the autorun script or runs the limine module at the path.
+#[derive(Debug, Clone)]
+pub struct BootConfig {
+    /// The maximum log level, otherwise the `LOG_LEVEL` the kernel was built with.
+    pub log: Option<LevelFilter>,
+    /// The name of the keyboard layout, which is checked by the architecture.
+    pub kbd: Option<String>,
+    /// The time zone.
+    pub tz: Option<TimeZone>,
+    /// Where the logs are written.
+    pub console: LogConsole,
+    /// If the kernel modules are loaded during boot.
+    pub modules: bool,
+    /// The script run once the kernel is set up.
+    pub autorun: Autorun,
+}
+
+impl BootConfig {
+    /// Parse the given options and return the config with the errors of the invalid options,
+    /// which are ignored.
+    pub fn parse(options: &str) -> (Self, Vec<ConfigError>) {
+        let mut config = Self::default();
+
+        let errors = options
+            .split_whitespace()
+            .filter_map(|option| {
+                let (key, value) = option.split_once('=').unwrap_or((option, ""));
+
+                config.set(key, value).err()
+            })
+            .collect();
+
+        (config, errors)
+    }
+
+    /// Set the option with the given key to the given value.
+    fn set(&mut self, key: &str, value: &str) -> Result<(), ConfigError> {
+        let invalid = |expected: &'static str| {
+            ConfigError::InvalidValue(key.to_string(), value.to_string(), expected)
+        };
+
+        if value.is_empty() {
+            return Err(ConfigError::MissingValue(key.to_string()));
+        }
+
+        match key {
+            "log" => {
+                self.log = Some(
+                    value
+                        .parse()
+                        .map_err(|_| invalid("off, error, warn, info, debug or trace"))?,
+                )
+            }
+            "kbd" => self.kbd = Some(value.to_string()),
+            "tz" => self.tz = Some(TimeZone::parse(value).ok_or_else(|| invalid("a time zone"))?),
+            "console" => {
+                self.console = match value {
+                    "both" => LogConsole::Both,
+                    "screen" => LogConsole::Screen,
+                    "serial" => LogConsole::Serial,
+                    _ => return Err(invalid("both, screen or serial")),
+                }
+            }
+            "modules" => {
+                self.modules = match value {
+                    "on" => true,
+                    "off" => false,
+                    _ => return Err(invalid("on or off")),
+                }
+            }
+            "autorun" => {
+                self.autorun = match value {
+                    "off" => Autorun::Off,
+                    path => Autorun::Module(path.to_string()),
+                }
+            }
+            _ => return Err(ConfigError::UnknownOption(key.to_string())),
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for BootConfig {
+    fn default() -> Self {
+        Self {
+            log: None,
+            kbd: None,
+            tz: None,
+            console: LogConsole::Both,
+            modules: true,
+            autorun: Autorun::Default,
+        }
+    }
+}
+
+/// Where the logs are written, set with `console=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LogConsole {
+    /// The logs are written to the control and the serial port.
+    Both,
+    /// The logs are only written to the control, once it is initialized.
+    Screen,
+    /// The logs are only written to the serial port, e.g. when running headless.
+    Serial,
+}
+
+impl Display for LogConsole {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            LogConsole::Both => write!(f, "both"),
+            LogConsole::Screen => write!(f, "screen"),
+            LogConsole::Serial => write!(f, "serial"),
+        }
+    }
+}
+
+/// The script run once the kernel is set up, set with `autorun=`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Autorun {
+    /// The limine module with the module string [AUTORUN](crate::control::script::AUTORUN).
+    Default,
+    /// No script is run.
+    Off,
+    /// The limine module at the given path.
+    Module(String),
+}
+
+impl Display for Autorun {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Autorun::Default => write!(f, "default"),
+            Autorun::Off => write!(f, "off"),
+            Autorun::Module(path) => write!(f, "{path}"),
+        }
+    }
+}
+
+/// Error type returned when parsing an option of the [BootConfig] fails.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ConfigError {
+    /// The option with the given key does not exist.
+    UnknownOption(String),
+    /// The option with the given key has no value.
+    MissingValue(String),
+    /// The option with the given key has the given invalid value instead of the expected one.
+    InvalidValue(String, String, &'static str),
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ConfigError::UnknownOption(key) => write!(f, "Unknown option '{key}'"),
+            ConfigError::MissingValue(key) => write!(f, "Expected '{key}=<value>'"),
+            ConfigError::InvalidValue(key, value, expected) => {
+                write!(
+                    f,
+                    "Invalid value '{value}' for '{key}', expected {expected}"
+                )
+            }
+        }
+    }
+}
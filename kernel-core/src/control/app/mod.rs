@@ -1,5 +1,8 @@
+use crate::sync::rwlock::RwLock;
 use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
 use alloc::string::String;
+use alloc::sync::Arc;
 use alloc::vec::Vec;
 use pc_keyboard::DecodedKey;
 use ratatui::Frame;
@@ -7,6 +10,78 @@ use ratatui::Frame;
 /// Contains the [breakout::BreakoutApp].
 pub mod breakout;
 
+/// Contains the [watch::WatchApp] of the `watch` command.
+pub mod watch;
+
+/// The registered apps, which can be launched by name.
+static APPS: RwLock<BTreeMap<&'static str, Registered>> = RwLock::new(BTreeMap::new());
+
+/// Creates a new instance of a registered [App].
+pub type AppFactory = fn() -> Box<dyn App>;
+
+/// A registered app with the counter of its live instances.
+struct Registered {
+    factory: AppFactory,
+    /// Cloned by every [Instance], so the strong count is the number of live instances plus one.
+    instances: Arc<()>,
+}
+
+/// Register an app under the given name.
+///
+/// Returns `false` if an app with the same name is already registered.
+pub fn register(name: &'static str, factory: AppFactory) -> bool {
+    APPS.run_mut(|apps| {
+        if apps.contains_key(name) {
+            return false;
+        }
+
+        apps.insert(
+            name,
+            Registered {
+                factory,
+                instances: Arc::new(()),
+            },
+        );
+
+        true
+    })
+}
+
+/// Unregister the app with the given name.
+///
+/// Returns `false` if there is no app with the given name.
+pub fn unregister(name: &str) -> bool {
+    APPS.run_mut(|apps| apps.remove(name).is_some())
+}
+
+/// Create a new instance of the app with the given name.
+///
+/// The instance and the apps it switches to are counted as instances of the registered app.
+pub fn create(name: &str) -> Option<Box<dyn App>> {
+    let (factory, instances) = APPS.run(|apps| {
+        apps.get(name)
+            .map(|app| (app.factory, app.instances.clone()))
+    })?;
+
+    Some(Box::new(Instance {
+        app: factory(),
+        instances,
+    }))
+}
+
+/// Returns if an instance of the app with the given name is still alive.
+pub fn is_live(name: &str) -> bool {
+    APPS.run(|apps| {
+        apps.get(name)
+            .is_some_and(|app| Arc::strong_count(&app.instances) > 1)
+    })
+}
+
+/// Returns the names of all registered apps in alphabetical order.
+pub fn names() -> Vec<&'static str> {
+    APPS.run(|apps| apps.keys().copied().collect())
+}
+
 /// An application run inside the [Control].
 pub trait App: Send + Sync + 'static {
     /// Render the app to the control frame.
@@ -28,3 +103,46 @@ pub enum AppCommand {
     /// Continue the app execution.
     Continue,
 }
+
+/// An instance of a registered app, which is counted as long as it's alive.
+struct Instance {
+    app: Box<dyn App>,
+    instances: Arc<()>,
+}
+
+impl Instance {
+    /// Count the apps the instance switches to as instances of the same registered app.
+    fn track(&self, command: AppCommand) -> AppCommand {
+        match command {
+            AppCommand::SetApp(app) => AppCommand::SetApp(Box::new(Instance {
+                app,
+                instances: self.instances.clone(),
+            })),
+            AppCommand::Multiple(commands) => AppCommand::Multiple(
+                commands
+                    .into_iter()
+                    .map(|command| self.track(command))
+                    .collect(),
+            ),
+            command => command,
+        }
+    }
+}
+
+impl App for Instance {
+    fn render(&mut self, frame: &mut Frame) -> AppCommand {
+        let command = self.app.render(frame);
+
+        self.track(command)
+    }
+
+    fn handle_input(&mut self, key: DecodedKey) -> AppCommand {
+        let command = self.app.handle_input(key);
+
+        self.track(command)
+    }
+
+    fn exit(&mut self) {
+        self.app.exit();
+    }
+}
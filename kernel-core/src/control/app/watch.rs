@@ -0,0 +1,167 @@
+use crate::control::app::{App, AppCommand};
+use crate::control::job::CancelToken;
+use crate::control::scrollback::Line;
+use crate::control::{Control, InnerControl};
+use crate::sync::mutex::Mutex;
+use crate::terminal::TerminalBox;
+use alloc::format;
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use pc_keyboard::DecodedKey;
+use ratatui::Frame;
+use ustyle::{Attributes, Color, Span, Style};
+
+/// Re-runs a command line periodically and shows its latest output full-screen,
+/// highlighting the chars that changed since the previous run.
+///
+/// Any key exits the app, which stops re-running the command line.
+pub struct WatchApp {
+    output: Arc<Mutex<WatchOutput>>,
+    token: CancelToken,
+}
+
+/// The output of the last two runs of a [WatchApp], updated by its timer.
+struct WatchOutput {
+    header: String,
+    lines: Vec<Line>,
+    previous: Vec<Line>,
+    runs: usize,
+}
+
+impl WatchApp {
+    const PARSE_CAPACITY: usize = 4;
+    const EXPANDED_TAB: &'static str = "    ";
+
+    /// Create the app and add the [Timer](crate::control::timer::Timer) to the control,
+    /// which runs the command line right away and then every `seconds` seconds.
+    pub fn start(control: &Control, line: String, seconds: u64) -> Self {
+        let token = CancelToken::new();
+        let output = Arc::new(Mutex::new(WatchOutput {
+            header: format!("Every {seconds}s: {line}"),
+            lines: Vec::new(),
+            previous: Vec::new(),
+            runs: 0,
+        }));
+
+        let shared = output.clone();
+        let millis = seconds.saturating_mul(1000);
+
+        control.add_timer(millis, token.clone(), move |control| {
+            let text = control
+                .capture(&line)
+                .unwrap_or_else(|err| format!("Error: {err}"));
+
+            let lines = Self::parse(&text);
+
+            shared.run(|output| {
+                output.previous = core::mem::replace(&mut output.lines, lines);
+                output.runs += 1;
+            });
+
+            true
+        });
+
+        Self { output, token }
+    }
+
+    /// Parse the styled output of the command line into lines.
+    ///
+    /// Output with malformed style markup, e.g. printed user text, is shown as is.
+    fn parse(text: &str) -> Vec<Line> {
+        let text = text.replace('\t', Self::EXPANDED_TAB);
+
+        let chars = match Span::decode_capacity(&text, Self::PARSE_CAPACITY) {
+            Ok(spans) => spans
+                .iter()
+                .flat_map(|span| span.text.chars().map(|ch| (ch, span.style)))
+                .collect::<Vec<_>>(),
+            Err(_) => text.chars().map(|ch| (ch, Self::style())).collect(),
+        };
+
+        let mut lines = Vec::new();
+        let mut current = Vec::new();
+
+        for (ch, style) in chars {
+            if ch == '\n' {
+                lines.push(core::mem::take(&mut current));
+            } else {
+                current.push((ch, style));
+            }
+        }
+
+        if !current.is_empty() {
+            lines.push(current);
+        }
+
+        lines
+    }
+
+    /// Returns the default style of the control.
+    fn style() -> Style {
+        Style::new(
+            InnerControl::FOREGROUND,
+            InnerControl::BACKGROUND,
+            Attributes::empty(),
+        )
+    }
+}
+
+impl App for WatchApp {
+    fn render(&mut self, frame: &mut Frame) -> AppCommand {
+        let area = frame.area();
+        let buf = frame.buffer_mut();
+
+        buf.reset();
+
+        let default = Self::style();
+        let changed = Style::new(Color::DarkerGray, Color::BrightYellow, Attributes::empty());
+
+        self.output.run(|output| {
+            let header = format!(
+                "{} (run {}, press any key to exit)",
+                output.header, output.runs
+            );
+            let header = header.chars().map(|ch| (ch, default)).collect::<Line>();
+            let empty = Line::new();
+
+            // The header is followed by an empty line
+            let rows = [&header, &empty]
+                .into_iter()
+                .chain(output.lines.iter())
+                .enumerate();
+
+            for (row, line) in rows.take(area.height as usize) {
+                let y = area.y + row as u16;
+
+                for (idx, (ch, style)) in line.iter().take(area.width as usize).enumerate() {
+                    // The first run has nothing to compare with
+                    let is_changed = row >= 2
+                        && output.runs > 1
+                        && output
+                            .previous
+                            .get(row - 2)
+                            .and_then(|previous| previous.get(idx))
+                            .is_none_or(|(previous, _)| previous != ch);
+
+                    let style = if is_changed { &changed } else { style };
+
+                    if let Some(cell) = buf.cell_mut((area.x + idx as u16, y)) {
+                        cell.set_char(*ch);
+                        cell.set_style(TerminalBox::style_to_tui(style, &default));
+                    }
+                }
+            }
+        });
+
+        AppCommand::Continue
+    }
+
+    fn handle_input(&mut self, _: DecodedKey) -> AppCommand {
+        AppCommand::Exit(None)
+    }
+
+    fn exit(&mut self) {
+        self.token.cancel();
+    }
+}
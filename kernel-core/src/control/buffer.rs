@@ -0,0 +1,39 @@
+use crate::sync::rwlock::RwLock;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// The named buffers command output can be redirected into.
+static BUFFERS: RwLock<BTreeMap<String, String>> = RwLock::new(BTreeMap::new());
+
+/// Write the given text to the buffer with the given name, creating it if necessary.
+///
+/// If `append` is `false`, the previous content of the buffer is replaced.
+pub fn write(name: &str, text: &str, append: bool) {
+    BUFFERS.run_mut(|buffers| {
+        let buffer = buffers.entry(name.to_string()).or_default();
+
+        if !append {
+            buffer.clear();
+        }
+
+        buffer.push_str(text);
+    })
+}
+
+/// Returns a copy of the content of the buffer with the given name.
+pub fn read(name: &str) -> Option<String> {
+    BUFFERS.run(|buffers| buffers.get(name).cloned())
+}
+
+/// Remove the buffer with the given name.
+///
+/// Returns `false` if there is no buffer with the given name.
+pub fn remove(name: &str) -> bool {
+    BUFFERS.run_mut(|buffers| buffers.remove(name).is_some())
+}
+
+/// Returns the names of all buffers in alphabetical order.
+pub fn names() -> Vec<String> {
+    BUFFERS.run(|buffers| buffers.keys().cloned().collect())
+}
@@ -1,372 +0,0 @@
-use alloc::string::String;
-
-pub use no_pico_args as args;
-
-/// A command that can be executed.
-#[derive(Copy, Clone, Debug)]
-pub struct Command {
-    /// The name of the command.
-    pub name: &'static str,
-    /// The description of the command.
-    pub description: &'static str,
-    /// The usage of the command. Displayed inside the `help` message.
-    pub usage: &'static str,
-    /// The function to run when the command is executed.
-    pub run: fn(String) -> Result<(), String>,
-}
-
-/// Built-in commands.
-pub mod builtin {
-    use crate::control::command::Command;
-    use crate::control::{CONTROL, app};
-    use crate::device::DeviceHub;
-    use crate::info::KernelInfo;
-    use crate::rand::{ChaCha20Rng, Pcg32Rng, Rng, Xoshiro256};
-    use crate::time::TimeZone;
-    use crate::{api, requests};
-    use alloc::boxed::Box;
-    use alloc::format;
-    use alloc::string::{String, ToString};
-    use core::fmt::Write;
-    use log::Level;
-    use no_pico_args::Arguments;
-    use time::UtcOffset;
-
-    /// Built-in commands.
-    pub const COMMANDS: &[Command] = &[
-        Command {
-            name: "clear",
-            description: "Clears the terminal buffer and screen.",
-            usage: "clear",
-            run: clear,
-        },
-        Command {
-            name: "sys-info",
-            description: "Prints information about the system to the control.",
-            usage: "sys-info",
-            run: sys_info,
-        },
-        Command {
-            name: "time",
-            description: "Prints the current time to the control or sets the time zone.",
-            usage: "time <help|local|utc|set <zone|+hh:+mm:+ss>|list>",
-            run: time,
-        },
-        Command {
-            name: "print",
-            description: "Prints a string to the control.",
-            usage: "print <string>",
-            run: print,
-        },
-        Command {
-            name: "log",
-            description: "Logs a message to the control.",
-            usage: "log <message>",
-            run: log,
-        },
-        Command {
-            name: "log",
-            description: "Logs a message to the control with a given level and optionally sets the logging level.",
-            usage: "log --level <level> [--set-level] <message>",
-            run: log,
-        },
-        Command {
-            name: "rand",
-            description: "Generate random data.",
-            usage: "rand [--quality] [--algo <pcg32|xoshiro256|chacha20>] <seed|int|uint|float|bool>",
-            run: rand,
-        },
-        Command {
-            name: "game",
-            description: "Run different games.",
-            usage: "game <breakout>",
-            run: game,
-        },
-        #[cfg(feature = "pci")]
-        Command {
-            name: "pci",
-            description: "Prints information about the PCI devices to the control.",
-            usage: "pci <info>",
-            run: pci,
-        },
-        #[cfg(feature = "qemu-exit")]
-        Command {
-            name: "exit",
-            description: "Exits the control via a QEMU exit command.",
-            usage: "exit [success|failure]",
-            run: exit,
-        },
-    ];
-
-    fn clear(_: String) -> Result<(), String> {
-        CONTROL.get().run(|ctrl| {
-            ctrl.lines.clear();
-            ctrl.string_buf.clear();
-        });
-
-        Ok(())
-    }
-
-    fn sys_info(_: String) -> Result<(), String> {
-        let info = KernelInfo::fetch();
-        let bootloader = requests::bootloader_info();
-
-        let info = format!(
-            "Running SubatomicOS by Mikail Plotzky\n\
-            \tBootloader: {} v{}\n\
-        \t{} v{}\n\
-        \t{} v{}",
-            bootloader.name(),
-            bootloader.version(),
-            info.core.package,
-            info.core.version,
-            info.api.package,
-            info.api.version,
-        );
-
-        log::info!("System information:\n{info}");
-
-        Ok(())
-    }
-
-    fn time(sub: String) -> Result<(), String> {
-        let mut args = Arguments::from_string(sub);
-
-        if let Some(sub) = args.subcommand() {
-            match sub.as_str() {
-                sub if sub == "local" || sub == "utc" => {
-                    let time = if sub == "local" {
-                        api::time().read_local()
-                    } else if sub == "utc" {
-                        api::time().read_utc().to_offset(UtcOffset::UTC)
-                    } else {
-                        api::time().read_local()
-                    };
-
-                    log::info!(
-                        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
-                        time.year(),
-                        time.month() as u8,
-                        time.day(),
-                        time.hour(),
-                        time.minute(),
-                        time.second()
-                    );
-                }
-
-                "set" => {
-                    let arg = args
-                        .subcommand()
-                        .ok_or("Please specify a new time offset".to_string())?;
-
-                    let zone =
-                        TimeZone::parse(&arg).ok_or(format!("Invalid time zone: {}", arg))?;
-                    let (hours, mins, secs) = zone.to_offset();
-                    api::time().set_offset(hours, mins, secs);
-
-                    log::info!(
-                        "Time offset updated with hours({}) minutes({}) seconds({})",
-                        hours,
-                        mins,
-                        secs
-                    );
-                }
-
-                "list" => {
-                    log::info!("Listing all named time zones:");
-
-                    for zone in TimeZone::NAMED_ZONES {
-                        log::info!("{} - {:?}", zone.as_symbol().unwrap(), zone);
-                    }
-                }
-
-                "help" => log::info!(
-                    "Print out the time or set the system time zone.\n\
-            \tUsage: `time <help|local|utc|set <zone>|list>`\n\
-            \tExample to get local time: `time local`\n\
-            \tExample to set the time zone to EST: `time set EST`\n\
-            \tExample to set the time zone to 10:30:00: `time set +10:+30:+00`\n\
-            \tExample to list all available time zones: `time list`"
-                ),
-
-                _ => {
-                    return Err(format!(
-                        "Invalid subcommand: {}. Usage: `time <help|local|utc|set <zone>|list>`.",
-                        sub
-                    ));
-                }
-            }
-        } else {
-            return Err(
-                "No subcommand specified. Usage: `time <help|local|utc|set <zone>|list>`."
-                    .to_string(),
-            );
-        }
-
-        Ok(())
-    }
-
-    fn print(sub: String) -> Result<(), String> {
-        CONTROL
-            .get()
-            .run(|ctrl| writeln!(ctrl, "{}", sub))
-            .map_err(|err| err.to_string())?;
-
-        Ok(())
-    }
-
-    fn log(sub: String) -> Result<(), String> {
-        let mut args = Arguments::from_string(sub);
-
-        let level: Level = args.value_from_str("--level").map_err(|err| match err {
-            no_pico_args::Error::MissingArgument => {
-                "Missing log level. Please use `--level <level>` to specify one.".to_string()
-            }
-            _ => err.to_string(),
-        })?;
-
-        let set_level = args.contains("--set-level");
-
-        if set_level {
-            unsafe {
-                log::set_max_level_racy(level.to_level_filter());
-            }
-        }
-
-        let rest = args.finish().join(" ");
-
-        log::log!(level, "{}", rest);
-
-        Ok(())
-    }
-
-    fn rand(sub: String) -> Result<(), String> {
-        let mut args = Arguments::from_string(sub);
-
-        let quality = args.contains("--quality");
-
-        let algo: String = args
-            .opt_value_from_str("--algo")
-            .map_err(|err| err.to_string())?
-            .unwrap_or("pcg32".to_string());
-
-        let ty = args
-            .subcommand()
-            .ok_or("No type specified. Command usage: `rand [--quality] [--algo <pcg32>] <seed|int|uint|float|bool>`.")?;
-
-        // TODO: add argument to specify bounds
-        macro_rules! gen_rand {
-            ($rng:expr, $ty:expr) => {
-                Box::new(|| {
-                    let mut result = Ok(());
-                    match $ty {
-                        "int" => log::info!("{}", $rng.int(i32::MIN..=i32::MAX)),
-                        "uint" => log::info!("{}", $rng.uint(u32::MIN..=u32::MAX)),
-                        "float" => log::info!("{}", $rng.float()),
-                        "bool" => log::info!("{}", $rng.bool()),
-                        "seed" => log::info!("{}", api::seed(quality)),
-
-                        _ => result = Err(format!(
-                            "Invalid type: {}. Available: 'seed', 'int', 'uint', 'float' and 'bool'.",
-                            $ty
-                        )),
-                    }
-
-                    return result;
-                })
-            }
-        }
-
-        let pcg32: Box<dyn Fn() -> Result<(), String>> =
-            gen_rand!(Pcg32Rng::new(quality), ty.as_str());
-
-        let xoshiro256: Box<dyn Fn() -> Result<(), String>> =
-            gen_rand!(Xoshiro256::new(quality), ty.as_str());
-
-        let chacha20: Box<dyn Fn() -> Result<(), String>> =
-            gen_rand!(ChaCha20Rng::new(quality), ty.as_str());
-
-        match algo.as_str() {
-            "pcg32" => pcg32(),
-            "xoshiro256" => xoshiro256(),
-            "chacha20" => chacha20(),
-            _ => Err(format!(
-                "Invalid algorithm: {}. Available: 'pcg32', 'xoshiro256' and 'chacha20'.",
-                algo
-            )),
-        }?;
-
-        Ok(())
-    }
-
-    fn game(sub: String) -> Result<(), String> {
-        let app = match sub.as_str() {
-            "breakout" => Ok(app::breakout::BreakoutApp::new()),
-            _ => Err("Game not found. Available: 'breakout'.".to_string()),
-        }?;
-
-        CONTROL.get().run(|ctrl| ctrl.set_app(app));
-
-        Ok(())
-    }
-
-    #[cfg(feature = "pci")]
-    fn pci(sub: String) -> Result<(), String> {
-        let mut args = Arguments::from_string(sub);
-
-        if let Some(sub) = args.subcommand() {
-            match sub.as_str() {
-                "info" => {
-                    api::without_interrupts(|| {
-                        crate::device::pci::PCI_HUB.get().run(|hub| {
-                            for (idx, dev) in hub.devices().iter().enumerate() {
-                                let dev = hub.get(*dev).expect("Failed to get device");
-                                let class = dev.class();
-                                let (ven_id, dev_id) = dev.id();
-
-                                log::info!(
-                                    "{idx}: Device at Address {}\n\
-                            \t- Header Type: {:?}\n\
-                            \t- Class: {:?}\n\
-                            \t- Interface: {}\n\
-                            \t- Revision: {}\n\
-                            \t- Device/Vendor ID: {:?}/{:?}\n\
-                            \t- Command: {:?}\n\
-                            \t- Capabilities: {:?}",
-                                    dev.addr(),
-                                    dev.header_type(),
-                                    class,
-                                    dev.interface(),
-                                    dev.revision(),
-                                    ven_id,
-                                    dev_id,
-                                    dev.command(),
-                                    dev.capabilities()
-                                );
-                            }
-                        })
-                    });
-                }
-                _ => return Err(format!("Invalid subcommand: {}. Usage: `pci <info>`.", sub)),
-            }
-        } else {
-            return Err("No subcommand specified. Usage: `pci <info>`.".to_string());
-        }
-
-        Ok(())
-    }
-
-    #[cfg(feature = "qemu-exit")]
-    fn exit(code: String) -> Result<(), String> {
-        let code = match code.as_str() {
-            "success" => crate::qemu::ExitCode::Success,
-            "failure" => crate::qemu::ExitCode::Failure,
-            "" => crate::qemu::ExitCode::Success,
-            _ => return Err("Invalid exit code. Available: 'success', 'failure'.".to_string()),
-        };
-
-        crate::qemu::exit(code);
-
-        Ok(())
-    }
-}
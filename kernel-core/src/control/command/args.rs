@@ -0,0 +1,329 @@
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+
+/// A declared argument of a [Command](super::Command).
+///
+/// Flags and options are matched by their full name including the dashes, e.g. `--level` or `-n`.
+/// Positional arguments are matched in declaration order, followed by the rest argument.
+#[derive(Copy, Clone, Debug)]
+pub struct Arg {
+    /// The name of the argument.
+    pub name: &'static str,
+    /// The description of the argument. Displayed inside the `help` message of the command.
+    pub description: &'static str,
+    /// The kind of the argument.
+    pub kind: ArgKind,
+    /// The type values of the argument are validated against.
+    pub ty: ArgType,
+    /// The value used if the argument is not given.
+    pub default: Option<&'static str>,
+    /// If the argument must be given.
+    pub required: bool,
+}
+
+/// The kind of an [Arg].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ArgKind {
+    /// A flag without a value, e.g. `--quality`.
+    Flag,
+    /// An option followed by a value, e.g. `--level info`.
+    Option,
+    /// A positional argument.
+    Positional,
+    /// All remaining arguments.
+    Rest,
+}
+
+/// The type of the value of an [Arg].
+#[derive(Copy, Clone, Debug)]
+pub enum ArgType {
+    /// Any string.
+    String,
+    /// A signed integer.
+    Int,
+    /// An unsigned integer.
+    UInt,
+    /// One of the given strings.
+    Choice(&'static [&'static str]),
+}
+
+impl ArgType {
+    /// Returns if the given value is valid for this type.
+    pub fn validate(&self, value: &str) -> bool {
+        match self {
+            ArgType::String => true,
+            ArgType::Int => value.parse::<i64>().is_ok(),
+            ArgType::UInt => value.parse::<u64>().is_ok(),
+            ArgType::Choice(choices) => choices.contains(&value),
+        }
+    }
+
+    /// Returns the valid values of a [ArgType::Choice] or nothing otherwise.
+    pub fn choices(&self) -> &'static [&'static str] {
+        match self {
+            ArgType::Choice(choices) => choices,
+            _ => &[],
+        }
+    }
+}
+
+impl Display for ArgType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ArgType::String => write!(f, "a string"),
+            ArgType::Int => write!(f, "an integer"),
+            ArgType::UInt => write!(f, "an unsigned integer"),
+            ArgType::Choice(choices) => write!(f, "one of '{}'", choices.join("', '")),
+        }
+    }
+}
+
+impl Arg {
+    /// Declare a flag without a value.
+    pub const fn flag(name: &'static str, description: &'static str) -> Self {
+        Self::new(name, description, ArgKind::Flag, ArgType::String, false)
+    }
+
+    /// Declare an optional option followed by a value of the given type.
+    pub const fn option(name: &'static str, description: &'static str, ty: ArgType) -> Self {
+        Self::new(name, description, ArgKind::Option, ty, false)
+    }
+
+    /// Declare a required positional argument of the given type.
+    pub const fn positional(name: &'static str, description: &'static str, ty: ArgType) -> Self {
+        Self::new(name, description, ArgKind::Positional, ty, true)
+    }
+
+    /// Declare an optional argument collecting all remaining arguments.
+    pub const fn rest(name: &'static str, description: &'static str) -> Self {
+        Self::new(name, description, ArgKind::Rest, ArgType::String, false)
+    }
+
+    const fn new(
+        name: &'static str,
+        description: &'static str,
+        kind: ArgKind,
+        ty: ArgType,
+        required: bool,
+    ) -> Self {
+        Self {
+            name,
+            description,
+            kind,
+            ty,
+            default: None,
+            required,
+        }
+    }
+
+    /// Make the argument required.
+    pub const fn required(mut self) -> Self {
+        self.required = true;
+        self
+    }
+
+    /// Make the argument optional.
+    pub const fn optional(mut self) -> Self {
+        self.required = false;
+        self
+    }
+
+    /// Make the argument optional and use the given value if it is not given.
+    pub const fn default(mut self, value: &'static str) -> Self {
+        self.default = Some(value);
+        self.required = false;
+        self
+    }
+
+    /// Returns the usage of the argument, e.g. `[--level <error|warn>]` or `<message...>`.
+    pub fn usage(&self) -> String {
+        let value = match self.ty {
+            ArgType::Choice(choices) => choices.join("|"),
+            _ => self.name.to_string(),
+        };
+
+        let usage = match self.kind {
+            ArgKind::Flag => self.name.to_string(),
+            ArgKind::Option => format!("{} <{value}>", self.name),
+            ArgKind::Positional => value,
+            ArgKind::Rest => format!("{value}..."),
+        };
+
+        match (self.kind, self.required) {
+            (ArgKind::Flag | ArgKind::Option, _) | (_, false) => format!("[{usage}]"),
+            _ => format!("<{usage}>"),
+        }
+    }
+
+    fn is_flag(&self) -> bool {
+        matches!(self.kind, ArgKind::Flag | ArgKind::Option)
+    }
+}
+
+/// Error type returned when the arguments of a command are invalid.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ArgError {
+    /// The given flag is not declared by the command.
+    UnknownFlag(String),
+    /// The option with the given name is not followed by a value.
+    MissingValue(&'static str),
+    /// The value is not valid for the argument with the given name,
+    /// which expects the given description of its type.
+    InvalidValue(&'static str, String, String),
+    /// The required argument with the given name is missing.
+    MissingArgument(&'static str),
+    /// The given argument is not declared by the command.
+    UnexpectedArgument(String),
+    /// The command requires one of its subcommands.
+    MissingSubcommand,
+    /// The given subcommand does not exist.
+    UnknownSubcommand(String),
+}
+
+impl Display for ArgError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ArgError::UnknownFlag(flag) => write!(f, "Unknown flag '{flag}'"),
+            ArgError::MissingValue(name) => write!(f, "Missing value for '{name}'"),
+            ArgError::InvalidValue(name, value, expected) => {
+                write!(
+                    f,
+                    "Invalid value '{value}' for '{name}', expected {expected}"
+                )
+            }
+            ArgError::MissingArgument(name) => write!(f, "Missing argument '{name}'"),
+            ArgError::UnexpectedArgument(arg) => write!(f, "Unexpected argument '{arg}'"),
+            ArgError::MissingSubcommand => write!(f, "Missing subcommand"),
+            ArgError::UnknownSubcommand(sub) => write!(f, "Unknown subcommand '{sub}'"),
+        }
+    }
+}
+
+/// The parsed and validated arguments of a command.
+#[derive(Debug, Default)]
+pub struct Args {
+    values: Vec<(&'static str, String)>,
+    flags: Vec<&'static str>,
+    rest: Vec<String>,
+}
+
+impl Args {
+    /// Parse the given whitespace separated arguments according to the declared arguments.
+    ///
+    /// Tokens starting with `-` are only treated as flags, if the command declares flags.
+    /// Once the first token of the rest argument was given, all tokens are added to it.
+    pub fn parse(declared: &[Arg], input: &str) -> Result<Self, ArgError> {
+        let positionals = declared
+            .iter()
+            .filter(|arg| arg.kind == ArgKind::Positional)
+            .collect::<Vec<_>>();
+
+        let rest = declared.iter().find(|arg| arg.kind == ArgKind::Rest);
+        let has_flags = declared.iter().any(Arg::is_flag);
+
+        let mut args = Self::default();
+        let mut next_positional = 0;
+        let mut tokens = input.split_whitespace();
+
+        while let Some(token) = tokens.next() {
+            let rest_started = !args.rest.is_empty()
+                || (rest.is_some()
+                    && !positionals.is_empty()
+                    && next_positional == positionals.len());
+
+            let is_flag = has_flags
+                && token.len() > 1
+                && token.starts_with('-')
+                && !token[1..].starts_with(|ch: char| ch.is_ascii_digit());
+
+            if is_flag && !rest_started {
+                let arg = declared
+                    .iter()
+                    .find(|arg| arg.is_flag() && arg.name == token)
+                    .ok_or_else(|| ArgError::UnknownFlag(token.to_string()))?;
+
+                if arg.kind == ArgKind::Flag {
+                    args.flags.push(arg.name);
+                } else {
+                    let value = tokens.next().ok_or(ArgError::MissingValue(arg.name))?;
+
+                    args.push(arg, value)?;
+                }
+            } else if let Some(arg) = positionals.get(next_positional)
+                && !rest_started
+            {
+                args.push(arg, token)?;
+                next_positional += 1;
+            } else if rest.is_some() {
+                args.rest.push(token.to_string());
+            } else {
+                return Err(ArgError::UnexpectedArgument(token.to_string()));
+            }
+        }
+
+        for arg in declared {
+            let given = match arg.kind {
+                ArgKind::Flag => continue,
+                ArgKind::Rest => !args.rest.is_empty(),
+                _ => args.str(arg.name).is_some(),
+            };
+
+            if given {
+                continue;
+            }
+
+            if arg.required {
+                return Err(ArgError::MissingArgument(arg.name));
+            }
+
+            if let Some(default) = arg.default {
+                args.values.push((arg.name, default.to_string()));
+            }
+        }
+
+        Ok(args)
+    }
+
+    fn push(&mut self, arg: &Arg, value: &str) -> Result<(), ArgError> {
+        if !arg.ty.validate(value) {
+            return Err(ArgError::InvalidValue(
+                arg.name,
+                value.to_string(),
+                arg.ty.to_string(),
+            ));
+        }
+
+        self.values.push((arg.name, value.to_string()));
+
+        Ok(())
+    }
+
+    /// Returns if the flag with the given name was given.
+    pub fn flag(&self, name: &str) -> bool {
+        self.flags.contains(&name)
+    }
+
+    /// Returns the value of the argument with the given name or its default value.
+    pub fn str(&self, name: &str) -> Option<&str> {
+        self.values
+            .iter()
+            .find(|(arg, _)| *arg == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns the value of the argument with the given name parsed as `T`.
+    ///
+    /// The value was already validated against the declared [ArgType],
+    /// so this only returns [None] if the argument was not given or `T` does not fit the value.
+    pub fn get<T: FromStr>(&self, name: &str) -> Option<T> {
+        self.str(name)?.parse().ok()
+    }
+
+    /// Returns the values of the rest argument.
+    pub fn rest(&self) -> &[String] {
+        &self.rest
+    }
+}
@@ -0,0 +1,1064 @@
+use crate::config::{self, BOOT_CONFIG};
+use crate::control::app::watch::WatchApp;
+use crate::control::command::args::{Arg, ArgType, Args};
+use crate::control::command::{Command, CommandIo};
+use crate::control::script::{Interpreter, Script, ScriptError};
+use crate::control::{CONTROL, InnerControl, app, buffer};
+use crate::device::DeviceHub;
+use crate::info::KernelInfo;
+use crate::module::{self, MODULES};
+use crate::process::table::{PROCESSES, Pid, ProcessState};
+use crate::rand::{ChaCha20Rng, Pcg32Rng, Rng, Xoshiro256};
+use crate::time::TimeZone;
+use crate::{api, process, requests};
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use log::Level;
+use time::UtcOffset;
+
+/// Built-in commands.
+pub const COMMANDS: &[Command] = &[
+    Command::new("clear", "Clears the terminal buffer and screen.", clear),
+    Command::new(
+        "console",
+        "Prints the active virtual console or switches to the given one.",
+        console,
+    )
+    .args(&[Arg::positional(
+        "index",
+        "The number of the console, starting at 1.",
+        ArgType::UInt,
+    )
+    .optional()]),
+    Command::new(
+        "scrollback",
+        "Prints or sets the maximum number of lines kept by each console.",
+        scrollback,
+    )
+    .args(&[Arg::positional("limit", "The maximum number of lines.", ArgType::UInt).optional()]),
+    Command::new(
+        "sys-info",
+        "Prints information about the system to the control.",
+        sys_info,
+    ),
+    Command::new(
+        "cmdline",
+        "Prints the kernel command line and the parsed boot options.",
+        cmdline,
+    ),
+    Command::group(
+        "time",
+        "Prints the current time to the control or sets the time zone.",
+        &[
+            Command::new("local", "Prints the local time.", time_local),
+            Command::new("utc", "Prints the UTC time.", time_utc),
+            Command::new("set", "Sets the time zone.", time_set)
+                .args(&[Arg::positional(
+                    "zone",
+                    "A named time zone like `EST` or an offset like `+10:+30:+00`.",
+                    ArgType::String,
+                )])
+                .completion(complete_zone),
+            Command::new("list", "Lists all named time zones.", time_list),
+        ],
+    ),
+    Command::new("print", "Prints a string to the control.", print)
+        .args(&[Arg::rest("text", "The text to print.")]),
+    Command::new(
+        "log",
+        "Logs a message to the control and optionally sets the logging level.",
+        log,
+    )
+    .args(&[
+        Arg::option(
+            "--level",
+            "The level of the message.",
+            ArgType::Choice(&["error", "warn", "info", "debug", "trace"]),
+        )
+        .default("info"),
+        Arg::flag("--set-level", "Also set the maximum logging level."),
+        Arg::rest("message", "The message to log."),
+    ]),
+    Command::new(
+        "grep",
+        "Prints the lines of the input containing the pattern.",
+        grep,
+    )
+    .args(&[
+        Arg::flag("-i", "Ignore the case."),
+        Arg::flag("-v", "Print the lines not containing the pattern."),
+        Arg::rest("pattern", "The pattern to search for.").required(),
+    ]),
+    Command::new("head", "Prints the first lines of the input.", head).args(&[Arg::option(
+        "-n",
+        "The number of lines.",
+        ArgType::UInt,
+    )
+    .default("10")]),
+    Command::new(
+        "wc",
+        "Prints the number of lines, words and bytes of the input.",
+        wc,
+    ),
+    Command::new("cat", "Prints the given buffers or the input.", cat)
+        .args(&[Arg::rest("buffer", "The buffers to print.")])
+        .completion(complete_buffer),
+    Command::new("rm", "Removes the given buffers.", rm)
+        .args(&[Arg::rest("buffer", "The buffers to remove.").required()])
+        .completion(complete_buffer),
+    Command::new(
+        "source",
+        "Runs the script in the given buffer or limine module.",
+        source,
+    )
+    .args(&[
+        Arg::positional(
+            "script",
+            "The name of the buffer or the path of the limine module.",
+            ArgType::String,
+        ),
+        Arg::rest("args", "The arguments of the script."),
+    ])
+    .completion(complete_script),
+    Command::new(
+        "test",
+        "Succeeds if the comparison is true. Used as condition in scripts.",
+        test,
+    )
+    .args(&[Arg::rest(
+        "expression",
+        "`<a> <=|!=|-eq|-ne|-lt|-le|-gt|-ge> <b>` or `<-z|-n> [string]`.",
+    )
+    .required()]),
+    Command::new(
+        "alias",
+        "Lists the aliases, prints an alias or defines an alias for a command line.",
+        alias,
+    )
+    .args(&[Arg::rest(
+        "definition",
+        "`name` to print or `name='command args'` to define an alias.",
+    )])
+    .completion(complete_alias),
+    Command::new("unalias", "Removes the given aliases.", unalias)
+        .args(&[Arg::rest("name", "The aliases to remove.").required()])
+        .completion(complete_alias),
+    Command::new("true", "Always succeeds.", |_, _| Ok(())),
+    Command::new("false", "Always fails.", |_, _| Err(String::new())),
+    Command::new("rand", "Generate random data.", rand).args(&[
+        Arg::flag("--quality", "Use a high quality seed."),
+        Arg::option(
+            "--algo",
+            "The random number generator.",
+            ArgType::Choice(&["pcg32", "xoshiro256", "chacha20"]),
+        )
+        .default("pcg32"),
+        Arg::positional(
+            "type",
+            "The type of the generated value.",
+            ArgType::Choice(&["seed", "int", "uint", "float", "bool"]),
+        ),
+    ]),
+    Command::new(
+        "watch",
+        "Runs a command periodically and shows its latest output until a key is pressed.",
+        watch,
+    )
+    .args(&[
+        Arg::option("-n", "The interval in seconds.", ArgType::UInt).default("2"),
+        Arg::rest("command", "The command with its arguments.").required(),
+    ]),
+    Command::new("game", "Run different games.", game)
+        .args(&[Arg::positional(
+            "name",
+            "The name of the game.",
+            ArgType::String,
+        )])
+        .completion(complete_game),
+    Command::new(
+        "run",
+        "Runs an executable shipped as limine module as foreground or background job.",
+        run,
+    )
+    .args(&[
+        Arg::flag("--background", "Run the executable as background job."),
+        Arg::positional("path", "The path of the limine module.", ArgType::String),
+        Arg::rest("args", "The arguments of the executable."),
+    ]),
+    Command::new(
+        "ps",
+        "Lists all processes with their state, CPU time and memory usage.",
+        ps,
+    ),
+    Command::new("kill", "Terminates the process with the given PID.", kill)
+        .args(&[Arg::positional(
+            "pid",
+            "The PID of the process.",
+            ArgType::UInt,
+        )])
+        .completion(complete_pid),
+    Command::new(
+        "wait",
+        "Waits for the process with the given PID to exit and prints its exit code.",
+        wait,
+    )
+    .args(&[Arg::positional(
+        "pid",
+        "The PID of the process.",
+        ArgType::UInt,
+    )])
+    .completion(complete_pid),
+    Command::new(
+        "jobs",
+        "Lists the background jobs started with a trailing `&` or cancels one.",
+        jobs,
+    )
+    .args(&[Arg::option(
+        "--cancel",
+        "The ID of the job to cancel.",
+        ArgType::UInt,
+    )]),
+    Command::group(
+        "module",
+        "Lists, inspects, loads and unloads kernel modules.",
+        &[
+            Command::new("list", "Lists all loaded modules.", module_list),
+            Command::new("info", "Prints information about a module.", module_info)
+                .args(&[Arg::positional(
+                    "name",
+                    "The name of the module.",
+                    ArgType::String,
+                )])
+                .completion(complete_module),
+            Command::new(
+                "load",
+                "Loads a module shipped as limine module.",
+                module_load,
+            )
+            .args(&[Arg::positional(
+                "path",
+                "The path of the limine module.",
+                ArgType::String,
+            )]),
+            Command::new("unload", "Unloads a module.", module_unload)
+                .args(&[Arg::positional(
+                    "name",
+                    "The name of the module.",
+                    ArgType::String,
+                )])
+                .completion(complete_module),
+        ],
+    ),
+    #[cfg(feature = "pci")]
+    Command::group(
+        "pci",
+        "Prints information about the PCI devices to the control.",
+        &[Command::new(
+            "info",
+            "Prints information about all PCI devices or the one at the given address.",
+            pci_info,
+        )
+        .args(&[Arg::positional(
+            "address",
+            "The address of the device, as listed by `pci info`.",
+            ArgType::String,
+        )
+        .optional()])
+        .completion(complete_pci_address)],
+    ),
+    #[cfg(feature = "qemu-exit")]
+    Command::new("exit", "Exits the control via a QEMU exit command.", exit).args(&[
+        Arg::positional(
+            "code",
+            "The exit code.",
+            ArgType::Choice(&["success", "failure"]),
+        )
+        .default("success"),
+    ]),
+];
+
+fn clear(_: &Args, _: &mut CommandIo) -> Result<(), String> {
+    CONTROL.get().run(|ctrl| ctrl.console().clear());
+
+    Ok(())
+}
+
+fn console(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let Some(index) = args.get::<usize>("index") else {
+        let active = CONTROL.get().run(|ctrl| ctrl.active());
+
+        writeln!(
+            io,
+            "Console {} of {} is active.",
+            active + 1,
+            InnerControl::CONSOLES
+        );
+
+        return Ok(());
+    };
+
+    if !CONTROL
+        .get()
+        .run(|ctrl| index > 0 && ctrl.switch(index - 1))
+    {
+        return Err(format!(
+            "Invalid console {index}. Available: 1 to {}.",
+            InnerControl::CONSOLES
+        ));
+    }
+
+    Ok(())
+}
+
+fn scrollback(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let Some(limit) = args.get::<usize>("limit") else {
+        let limit = CONTROL.get().run(|ctrl| ctrl.scrollback_limit());
+
+        writeln!(io, "Each console keeps up to {limit} lines.");
+
+        return Ok(());
+    };
+
+    if limit == 0 {
+        return Err("The scrollback limit must be at least 1.".to_string());
+    }
+
+    CONTROL.get().run(|ctrl| ctrl.set_scrollback_limit(limit));
+
+    Ok(())
+}
+
+fn sys_info(_: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let info = KernelInfo::fetch();
+    let bootloader = requests::bootloader_info();
+
+    let info = format!(
+        "Running SubatomicOS by Mikail Plotzky\n\
+        \tBootloader: {} v{}\n\
+    \t{} v{}\n\
+    \t{} v{}",
+        bootloader.name(),
+        bootloader.version(),
+        info.core.package,
+        info.core.version,
+        info.api.package,
+        info.api.version,
+    );
+
+    writeln!(io, "System information:\n{info}");
+
+    Ok(())
+}
+
+fn cmdline(_: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let options = BOOT_CONFIG.get();
+    let (_, statements) = config::split_cmdline(requests::cmdline());
+
+    let or_default = |value: Option<String>| value.unwrap_or_else(|| "default".to_string());
+
+    writeln!(
+        io,
+        "Command line: {}\n\
+        Boot options:\n\
+        \tlog: {}\n\
+        \tkbd: {}\n\
+        \ttz: {}\n\
+        \tconsole: {}\n\
+        \tmodules: {}\n\
+        \tautorun: {}\n\
+        Profile: {}",
+        requests::cmdline(),
+        or_default(options.log.map(|level| level.to_string())),
+        or_default(options.kbd.clone()),
+        or_default(options.tz.map(|zone| {
+            zone.as_symbol()
+                .map_or_else(|| format!("{zone:?}"), String::from)
+        })),
+        options.console,
+        if options.modules { "on" } else { "off" },
+        options.autorun,
+        statements.unwrap_or_default()
+    );
+
+    Ok(())
+}
+
+fn time_local(_: &Args, io: &mut CommandIo) -> Result<(), String> {
+    print_time(api::time().read_local(), io);
+
+    Ok(())
+}
+
+fn time_utc(_: &Args, io: &mut CommandIo) -> Result<(), String> {
+    print_time(api::time().read_utc().to_offset(UtcOffset::UTC), io);
+
+    Ok(())
+}
+
+fn print_time(time: time::OffsetDateTime, io: &mut CommandIo) {
+    writeln!(
+        io,
+        "{:04}-{:02}-{:02} {:02}:{:02}:{:02}",
+        time.year(),
+        time.month() as u8,
+        time.day(),
+        time.hour(),
+        time.minute(),
+        time.second()
+    );
+}
+
+fn time_set(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let zone = args.str("zone").unwrap_or_default();
+
+    let zone = TimeZone::parse(zone).ok_or(format!("Invalid time zone: {}", zone))?;
+    let (hours, mins, secs) = zone.to_offset();
+    api::time().set_offset(hours, mins, secs);
+
+    writeln!(
+        io,
+        "Time offset updated with hours({}) minutes({}) seconds({})",
+        hours, mins, secs
+    );
+
+    Ok(())
+}
+
+fn time_list(_: &Args, io: &mut CommandIo) -> Result<(), String> {
+    writeln!(io, "Listing all named time zones:");
+
+    for zone in TimeZone::NAMED_ZONES {
+        writeln!(io, "{} - {:?}", zone.as_symbol().unwrap(), zone);
+    }
+
+    Ok(())
+}
+
+fn complete_zone(args: &[&str]) -> Vec<String> {
+    match args {
+        [] => TimeZone::NAMED_ZONES
+            .iter()
+            .filter_map(TimeZone::as_symbol)
+            .map(ToString::to_string)
+            .collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn print(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    writeln!(io, "{}", args.rest().join(" "));
+
+    Ok(())
+}
+
+fn log(args: &Args, _: &mut CommandIo) -> Result<(), String> {
+    let level: Level = args.get("--level").unwrap_or(Level::Info);
+
+    if args.flag("--set-level") {
+        unsafe {
+            log::set_max_level_racy(level.to_level_filter());
+        }
+    }
+
+    // Only setting the level without a message, e.g. inside the profile
+    if !args.rest().is_empty() {
+        log::log!(level, "{}", args.rest().join(" "));
+    }
+
+    Ok(())
+}
+
+fn grep(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let ignore_case = args.flag("-i");
+    let invert = args.flag("-v");
+
+    let pattern = args.rest().join(" ");
+
+    let pattern = if ignore_case {
+        pattern.to_lowercase()
+    } else {
+        pattern
+    };
+
+    let lines = io
+        .input()
+        .lines()
+        .filter(|line| {
+            let matches = if ignore_case {
+                line.to_lowercase().contains(&pattern)
+            } else {
+                line.contains(&pattern)
+            };
+
+            matches != invert
+        })
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+
+    for line in lines {
+        writeln!(io, "{line}");
+    }
+
+    Ok(())
+}
+
+fn head(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let count = args
+        .get("-n")
+        .ok_or("The line count is too large.".to_string())?;
+
+    let lines = io
+        .input()
+        .lines()
+        .take(count)
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+
+    for line in lines {
+        writeln!(io, "{line}");
+    }
+
+    Ok(())
+}
+
+fn wc(_: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let input = io.input();
+    let (lines, words, bytes) = (
+        input.lines().count(),
+        input.split_whitespace().count(),
+        input.len(),
+    );
+
+    writeln!(io, "{lines} {words} {bytes}");
+
+    Ok(())
+}
+
+fn cat(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    if args.rest().is_empty() {
+        let input = io.input().to_string();
+
+        write!(io, "{input}");
+
+        return Ok(());
+    }
+
+    for name in args.rest() {
+        let text = buffer::read(name).ok_or_else(|| format!("No buffer named '{name}'."))?;
+
+        write!(io, "{text}");
+    }
+
+    Ok(())
+}
+
+fn rm(args: &Args, _: &mut CommandIo) -> Result<(), String> {
+    for name in args.rest() {
+        if !buffer::remove(name) {
+            return Err(format!("No buffer named '{name}'."));
+        }
+    }
+
+    Ok(())
+}
+
+fn complete_buffer(_: &[&str]) -> Vec<String> {
+    buffer::names()
+}
+
+fn source(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let name = args.str("script").unwrap_or_default();
+
+    let source = match buffer::read(name) {
+        Some(source) => source,
+        None => {
+            let file = requests::module_file(name)
+                .ok_or_else(|| format!("No buffer or limine module named '{name}'."))?;
+
+            let bytes = unsafe {
+                core::slice::from_raw_parts(file.addr() as *const u8, file.size() as usize)
+            };
+
+            String::from_utf8(bytes.to_vec())
+                .map_err(|_| format!("Script '{name}' is not valid UTF-8."))?
+        }
+    };
+
+    let script = Script::parse(&source).map_err(|err| format!("{name}: {err}."))?;
+
+    let succeeded = Interpreter::new(CONTROL.get(), io, args.rest().to_vec())
+        .run(&script)
+        .map_err(|err| match err {
+            // Reported by the control
+            ScriptError::Cancelled(_) => String::new(),
+            err => format!("{name}: {err}."),
+        })?;
+
+    // Failing without a message, since the failed command was already logged
+    if !succeeded {
+        return Err(String::new());
+    }
+
+    Ok(())
+}
+
+fn complete_script(args: &[&str]) -> Vec<String> {
+    match args {
+        [] => buffer::names(),
+        _ => Vec::new(),
+    }
+}
+
+fn alias(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let control = CONTROL.get();
+    let definition = args.rest().join(" ");
+
+    if definition.is_empty() {
+        for (name, line) in control.aliases() {
+            writeln!(io, "alias {name}='{line}'");
+        }
+
+        return Ok(());
+    }
+
+    let Some((name, line)) = definition.split_once('=') else {
+        let line = control
+            .alias(&definition)
+            .ok_or_else(|| format!("No alias named '{definition}'."))?;
+
+        writeln!(io, "alias {definition}='{line}'");
+
+        return Ok(());
+    };
+
+    let line = line.trim();
+    let line = ['\'', '"']
+        .into_iter()
+        .find_map(|quote| line.strip_prefix(quote)?.strip_suffix(quote))
+        .unwrap_or(line);
+
+    if !control.set_alias(name.trim(), line.trim()) {
+        return Err(format!("Invalid alias name '{}'.", name.trim()));
+    }
+
+    Ok(())
+}
+
+fn unalias(args: &Args, _: &mut CommandIo) -> Result<(), String> {
+    for name in args.rest() {
+        if !CONTROL.get().remove_alias(name) {
+            return Err(format!("No alias named '{name}'."));
+        }
+    }
+
+    Ok(())
+}
+
+fn complete_alias(_: &[&str]) -> Vec<String> {
+    CONTROL
+        .get()
+        .aliases()
+        .into_iter()
+        .map(|(name, _)| name)
+        .collect()
+}
+
+fn test(args: &Args, _: &mut CommandIo) -> Result<(), String> {
+    let args = args.rest().iter().map(String::as_str).collect::<Vec<_>>();
+
+    let number = |arg: &str| {
+        arg.parse::<i64>()
+            .map_err(|_| format!("Invalid number: {arg}."))
+    };
+
+    let result = match args.as_slice() {
+        ["-z"] => true,
+        ["-n"] => false,
+        ["-z", string] => string.is_empty(),
+        ["-n", string] => !string.is_empty(),
+        [a, "=", b] => a == b,
+        [a, "!=", b] => a != b,
+        [a, "-eq", b] => number(a)? == number(b)?,
+        [a, "-ne", b] => number(a)? != number(b)?,
+        [a, "-lt", b] => number(a)? < number(b)?,
+        [a, "-le", b] => number(a)? <= number(b)?,
+        [a, "-gt", b] => number(a)? > number(b)?,
+        [a, "-ge", b] => number(a)? >= number(b)?,
+        _ => return Err("Invalid comparison. See `help test`.".to_string()),
+    };
+
+    if !result {
+        return Err(String::new());
+    }
+
+    Ok(())
+}
+
+fn rand(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let quality = args.flag("--quality");
+    let algo = args.str("--algo").unwrap_or_default();
+    let ty = args.str("type").unwrap_or_default();
+
+    // TODO: add argument to specify bounds
+    macro_rules! gen_rand {
+        ($rng:expr, $ty:expr) => {
+            Box::new(|| match $ty {
+                "int" => $rng.int(i32::MIN..=i32::MAX).to_string(),
+                "uint" => $rng.uint(u32::MIN..=u32::MAX).to_string(),
+                "float" => $rng.float().to_string(),
+                "bool" => $rng.bool().to_string(),
+                _ => api::seed(quality).to_string(),
+            })
+        };
+    }
+
+    let pcg32: Box<dyn Fn() -> String> = gen_rand!(Pcg32Rng::new(quality), ty);
+    let xoshiro256: Box<dyn Fn() -> String> = gen_rand!(Xoshiro256::new(quality), ty);
+    let chacha20: Box<dyn Fn() -> String> = gen_rand!(ChaCha20Rng::new(quality), ty);
+
+    let value = match algo {
+        "xoshiro256" => xoshiro256(),
+        "chacha20" => chacha20(),
+        _ => pcg32(),
+    };
+
+    writeln!(io, "{value}");
+
+    Ok(())
+}
+
+fn watch(args: &Args, _: &mut CommandIo) -> Result<(), String> {
+    let seconds = args.get::<u64>("-n").unwrap_or(2).max(1);
+    let control = CONTROL.get();
+
+    let app = WatchApp::start(control, args.rest().join(" "), seconds);
+
+    control.run(|ctrl| ctrl.set_app(Box::new(app)));
+
+    Ok(())
+}
+
+fn game(args: &Args, _: &mut CommandIo) -> Result<(), String> {
+    let app = app::create(args.str("name").unwrap_or_default()).ok_or_else(|| {
+        format!(
+            "Game not found. Available: '{}'.",
+            app::names().join("', '")
+        )
+    })?;
+
+    CONTROL.get().run(|ctrl| ctrl.set_app(app));
+
+    Ok(())
+}
+
+fn complete_game(args: &[&str]) -> Vec<String> {
+    match args {
+        [] => app::names().into_iter().map(ToString::to_string).collect(),
+        _ => Vec::new(),
+    }
+}
+
+fn run(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let path = args.str("path").unwrap_or_default();
+    let rest = args.rest().iter().map(String::as_str).collect::<Vec<_>>();
+
+    let pid =
+        process::spawn(path, &rest).map_err(|err| format!("Failed to run '{path}': {err}."))?;
+
+    if args.flag("--background") {
+        writeln!(io, "Started process '{path}' with PID {pid}.");
+    } else {
+        PROCESSES.run(|table| table.set_foreground(pid));
+    }
+
+    Ok(())
+}
+
+fn ps(_: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let tick_millis = api::process().tick_millis();
+
+    let list = PROCESSES.run(|table| {
+        let mut list = format!(
+            "{:>5}  {:<10}  {:>10}  {:>10}  NAME\n",
+            "PID", "STATE", "CPU TIME", "MEMORY"
+        );
+
+        for entry in table.iter() {
+            list.push_str(&format!(
+                "{:>5}  {:<10}  {:>8}ms  {:>8}KB  {}\n",
+                entry.pid(),
+                entry.state().to_string(),
+                entry.ticks() * tick_millis,
+                entry.memory() / 1024,
+                entry.name()
+            ));
+        }
+
+        list
+    });
+
+    writeln!(io, "Processes:\n{list}");
+
+    Ok(())
+}
+
+fn kill(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let pid = pid(args)?;
+
+    if !PROCESSES.run(|table| table.kill(pid)) {
+        return Err(format!("No running process with PID {pid}."));
+    }
+
+    writeln!(io, "Killed process with PID {pid}.");
+
+    Ok(())
+}
+
+fn wait(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let pid = pid(args)?;
+
+    // Reap the process if it already terminated, otherwise make it the foreground job
+    let state = PROCESSES
+        .run(|table| {
+            table
+                .reap(pid)
+                .or_else(|| table.set_foreground(pid).then_some(ProcessState::Running))
+        })
+        .ok_or_else(|| format!("No process with PID {pid}."))?;
+
+    match state {
+        ProcessState::Exited(code) => {
+            writeln!(io, "Process with PID {pid} exited with code {code}.")
+        }
+        ProcessState::Killed => writeln!(io, "Process with PID {pid} was killed."),
+        // The exit is logged once the foreground job terminates
+        _ => (),
+    }
+
+    Ok(())
+}
+
+fn jobs(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let control = CONTROL.get();
+
+    if let Some(id) = args.get::<usize>("--cancel") {
+        if !control.cancel_job(id) {
+            return Err(format!("No job with ID {id}."));
+        }
+
+        writeln!(io, "Cancelled job [{id}].");
+
+        return Ok(());
+    }
+
+    let mut list = format!("{:>5}  {:<10}  {:>7}  COMMAND\n", "ID", "STATE", "CONSOLE");
+
+    for job in control.jobs() {
+        list.push_str(&format!(
+            "{:>5}  {:<10}  {:>7}  {}\n",
+            job.id,
+            job.state.to_string(),
+            job.console + 1,
+            job.line
+        ));
+    }
+
+    writeln!(io, "Jobs:\n{list}");
+
+    Ok(())
+}
+
+fn pid(args: &Args) -> Result<Pid, String> {
+    let pid = args.str("pid").unwrap_or_default();
+
+    pid.parse().map_err(|_| format!("Invalid PID: {pid}."))
+}
+
+fn complete_pid(args: &[&str]) -> Vec<String> {
+    match args {
+        [] => PROCESSES.run(|table| table.iter().map(|entry| entry.pid().to_string()).collect()),
+        _ => Vec::new(),
+    }
+}
+
+fn module_list(_: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let list = MODULES.run(|modules| {
+        let mut list = format!(
+            "{:<16}  {:<10}  {:<16}  {:<10}  {:<18}  {:>8}\n",
+            "NAME", "VERSION", "AUTHOR", "STATE", "BASE", "SIZE"
+        );
+
+        for loaded in modules {
+            list.push_str(&format!(
+                "{:<16}  {:<10}  {:<16}  {:<10}  {:#018x}  {:>6}KB\n",
+                loaded.module.name,
+                loaded.module.version,
+                loaded.module.author,
+                loaded.state().to_string(),
+                loaded.image.addr(),
+                loaded.image.size() / 1024
+            ));
+        }
+
+        list
+    });
+
+    writeln!(io, "Modules:\n{list}");
+
+    Ok(())
+}
+
+fn module_info(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let name = args.str("name").unwrap_or_default();
+
+    let info = MODULES
+        .run(|modules| {
+            modules
+                .iter()
+                .find(|loaded| loaded.module.name == name)
+                .map(|loaded| {
+                    format!(
+                        "Module {}:\n\
+                        \t- Version: {}\n\
+                        \t- Author: {}\n\
+                        \t- Description: {}\n\
+                        \t- Dependencies: {}\n\
+                        \t- Provides: {}\n\
+                        \t- State: {}\n\
+                        \t- Base Address: {:#x}\n\
+                        \t- Size: {} bytes",
+                        loaded.module.name,
+                        loaded.module.version,
+                        loaded.module.author,
+                        loaded.module.description,
+                        loaded.module.dependencies.join(", "),
+                        loaded.module.provides.join(", "),
+                        loaded.state(),
+                        loaded.image.addr(),
+                        loaded.image.size()
+                    )
+                })
+        })
+        .ok_or_else(|| format!("No module named '{name}'."))?;
+
+    writeln!(io, "{info}");
+
+    Ok(())
+}
+
+fn module_load(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let path = args.str("path").unwrap_or_default();
+
+    let name =
+        module::load(path).map_err(|err| format!("Failed to load module '{path}': {err}."))?;
+
+    writeln!(io, "Loaded module {name} from '{path}'.");
+
+    Ok(())
+}
+
+fn module_unload(args: &Args, _: &mut CommandIo) -> Result<(), String> {
+    let name = args.str("name").unwrap_or_default();
+
+    module::unload(name).map_err(|err| format!("Failed to unload module '{name}': {err}."))
+}
+
+fn complete_module(args: &[&str]) -> Vec<String> {
+    match args {
+        [] => MODULES.run(|modules| {
+            modules
+                .iter()
+                .map(|loaded| loaded.module.name.clone())
+                .collect()
+        }),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(feature = "pci")]
+fn pci_info(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let pci = crate::device::pci::PCI_HUB.get();
+    let devices = api::without_interrupts(|| pci.run(|hub| hub.devices()));
+    let address = args.str("address");
+    let mut found = false;
+
+    for (idx, dev) in devices.into_iter().enumerate() {
+        // Unlocked between devices, so large buses can be cancelled
+        io.checkpoint()?;
+
+        api::without_interrupts(|| {
+            pci.run(|hub| {
+                let dev = hub.get(dev).expect("Failed to get device");
+
+                if address.is_some_and(|address| dev.addr().to_string() != address) {
+                    return;
+                }
+
+                found = true;
+                let class = dev.class();
+                let (ven_id, dev_id) = dev.id();
+
+                writeln!(
+                    io,
+                    "{idx}: Device at Address {}\n\
+                    \t- Header Type: {:?}\n\
+                    \t- Class: {:?}\n\
+                    \t- Interface: {}\n\
+                    \t- Revision: {}\n\
+                    \t- Device/Vendor ID: {:?}/{:?}\n\
+                    \t- Command: {:?}\n\
+                    \t- Capabilities: {:?}",
+                    dev.addr(),
+                    dev.header_type(),
+                    class,
+                    dev.interface(),
+                    dev.revision(),
+                    ven_id,
+                    dev_id,
+                    dev.command(),
+                    dev.capabilities()
+                );
+            })
+        });
+    }
+
+    if let Some(address) = address
+        && !found
+    {
+        return Err(format!("No PCI device at address '{address}'."));
+    }
+
+    Ok(())
+}
+
+#[cfg(feature = "pci")]
+fn complete_pci_address(args: &[&str]) -> Vec<String> {
+    let pci = crate::device::pci::PCI_HUB.get();
+
+    match args {
+        [] => api::without_interrupts(|| {
+            pci.run(|hub| {
+                hub.devices()
+                    .into_iter()
+                    .filter_map(|dev| hub.get(dev).ok())
+                    .map(|dev| dev.addr().to_string())
+                    .collect()
+            })
+        }),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(feature = "qemu-exit")]
+fn exit(args: &Args, _: &mut CommandIo) -> Result<(), String> {
+    let code = match args.str("code") {
+        Some("failure") => crate::qemu::ExitCode::Failure,
+        _ => crate::qemu::ExitCode::Success,
+    };
+
+    crate::qemu::exit(code);
+
+    Ok(())
+}
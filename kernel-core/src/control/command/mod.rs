@@ -0,0 +1,360 @@
+use crate::control::CONTROL;
+use crate::control::command::args::{Arg, ArgError, ArgKind, Args};
+use crate::control::job::CancelToken;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Arguments, Write};
+
+/// Contains the [Arg] declarations and the parsed [Args] of commands.
+pub mod args;
+
+/// Contains the built-in commands.
+pub mod builtin;
+
+/// The function run when a [Command] is executed.
+///
+/// An empty error fails the command without logging a message.
+pub type CommandFn = fn(&Args, &mut CommandIo) -> Result<(), String>;
+
+/// A command that can be executed, declared with its arguments or subcommands.
+///
+/// The usage and the `help <command>` message are generated from the declaration
+/// and the arguments are validated before the command is run.
+///
+/// ```ignore
+/// const ECHO: Command = Command::new("echo", "Prints the message.", echo)
+///     .args(&[
+///         Arg::flag("-n", "Omit the trailing new line."),
+///         Arg::rest("message", "The message to print."),
+///     ]);
+/// ```
+#[derive(Copy, Clone, Debug)]
+pub struct Command {
+    /// The name of the command.
+    pub name: &'static str,
+    /// The description of the command.
+    pub description: &'static str,
+    /// The declared arguments of the command.
+    pub args: &'static [Arg],
+    /// The subcommands of the command. If not empty, one of them must be given.
+    pub subcommands: &'static [Command],
+    /// The function to run with the parsed arguments. Not called for commands with subcommands.
+    pub run: Option<CommandFn>,
+    /// The function returning additional completion candidates for the argument
+    /// following the given arguments, e.g. names that are only known at runtime.
+    ///
+    /// The candidates are filtered by the partially typed argument by the control.
+    pub complete: Option<fn(&[&str]) -> Vec<String>>,
+}
+
+impl Command {
+    /// Declare a command without arguments, which runs the given function.
+    pub const fn new(name: &'static str, description: &'static str, run: CommandFn) -> Self {
+        Self {
+            name,
+            description,
+            args: &[],
+            subcommands: &[],
+            run: Some(run),
+            complete: None,
+        }
+    }
+
+    /// Declare a command, which requires one of the given subcommands.
+    pub const fn group(
+        name: &'static str,
+        description: &'static str,
+        subcommands: &'static [Command],
+    ) -> Self {
+        Self {
+            name,
+            description,
+            args: &[],
+            subcommands,
+            run: None,
+            complete: None,
+        }
+    }
+
+    /// Declare the arguments of the command.
+    pub const fn args(mut self, args: &'static [Arg]) -> Self {
+        self.args = args;
+        self
+    }
+
+    /// Set the function returning additional completion candidates.
+    pub const fn completion(mut self, complete: fn(&[&str]) -> Vec<String>) -> Self {
+        self.complete = Some(complete);
+        self
+    }
+
+    /// Execute the command with the given arguments.
+    ///
+    /// Invalid arguments are reported with the usage of the command.
+    pub fn execute(&self, args: &str, io: &mut CommandIo) -> Result<(), String> {
+        let (command, path, args) = self
+            .resolve(args)
+            .map_err(|(command, path, err)| format!("{err}. Usage: `{}`.", command.usage(&path)))?;
+
+        let args = Args::parse(command.args, args)
+            .map_err(|err| format!("{err}. Usage: `{}`.", command.usage(&path)))?;
+
+        match command.run {
+            Some(run) => run(&args, io),
+            None => Ok(()),
+        }
+    }
+
+    /// Find the subcommand selected by the leading arguments.
+    ///
+    /// Returns the subcommand, its full name and the remaining arguments.
+    /// On failure, the innermost command that was found is returned with the error.
+    #[allow(clippy::type_complexity)]
+    fn resolve<'a>(
+        &self,
+        args: &'a str,
+    ) -> Result<(&Self, String, &'a str), (&Self, String, ArgError)> {
+        let mut command = self;
+        let mut path = self.name.to_string();
+        let mut args = args.trim();
+
+        while !command.subcommands.is_empty() {
+            let (name, rest) = args.split_once(' ').unwrap_or((args, ""));
+
+            let Some(sub) = command.subcommands.iter().find(|sub| sub.name == name) else {
+                let err = if name.is_empty() {
+                    ArgError::MissingSubcommand
+                } else {
+                    ArgError::UnknownSubcommand(name.to_string())
+                };
+
+                return Err((command, path, err));
+            };
+
+            command = sub;
+            path = format!("{path} {name}");
+            args = rest.trim();
+        }
+
+        Ok((command, path, args))
+    }
+
+    /// Returns the usage of the command with the given full name, e.g. `time <local|utc>`.
+    pub fn usage(&self, path: &str) -> String {
+        if !self.subcommands.is_empty() {
+            let names = self
+                .subcommands
+                .iter()
+                .map(|sub| sub.name)
+                .collect::<Vec<_>>();
+
+            return format!("{path} <{}>", names.join("|"));
+        }
+
+        self.args.iter().fold(path.to_string(), |usage, arg| {
+            format!("{usage} {}", arg.usage())
+        })
+    }
+
+    /// Returns the detailed help message of the subcommand selected by the given arguments.
+    pub fn help(&self, args: &str) -> String {
+        let (command, path) = match self.resolve(args) {
+            Ok((command, path, _)) | Err((command, path, _)) => (command, path),
+        };
+
+        let mut help = format!(
+            "{path} - {}\n\tUsage: {}\n",
+            command.description,
+            command.usage(&path)
+        );
+
+        if !command.subcommands.is_empty() {
+            help.push_str("\tSubcommands:\n");
+
+            for sub in command.subcommands {
+                help.push_str(&format!("\t\t{} - {}\n", sub.name, sub.description));
+            }
+        }
+
+        if !command.args.is_empty() {
+            help.push_str("\tArguments:\n");
+
+            for arg in command.args {
+                help.push_str(&format!("\t\t{} - {}", arg.usage(), arg.description));
+
+                if let Some(default) = arg.default {
+                    help.push_str(&format!(" (default: {default})"));
+                }
+
+                help.push('\n');
+            }
+        }
+
+        help
+    }
+
+    /// Returns the completion candidates for the argument following the given arguments.
+    ///
+    /// These are the subcommands, the choices of the next argument, the flags that were not
+    /// given yet and the candidates of the [Command::complete] function.
+    pub fn candidates(&self, args: &[&str]) -> Vec<String> {
+        if !self.subcommands.is_empty() {
+            return match args.split_first() {
+                None => self
+                    .subcommands
+                    .iter()
+                    .map(|sub| sub.name.to_string())
+                    .collect(),
+                Some((name, args)) => self
+                    .subcommands
+                    .iter()
+                    .find(|sub| sub.name == *name)
+                    .map_or_else(Vec::new, |sub| sub.candidates(args)),
+            };
+        }
+
+        let flag = |name: &str| {
+            self.args
+                .iter()
+                .find(|arg| matches!(arg.kind, ArgKind::Flag | ArgKind::Option) && arg.name == name)
+        };
+
+        // The value of an option
+        if let Some(arg) = args.last().and_then(|last| flag(last))
+            && arg.kind == ArgKind::Option
+        {
+            return arg.ty.choices().iter().map(ToString::to_string).collect();
+        }
+
+        // Count the given positional arguments, skipping flags and option values
+        let mut positional = 0;
+        let mut iter = args.iter();
+
+        while let Some(arg) = iter.next() {
+            match flag(arg) {
+                Some(arg) if arg.kind == ArgKind::Option => {
+                    iter.next();
+                }
+                Some(_) => (),
+                None => positional += 1,
+            }
+        }
+
+        let mut candidates = self
+            .args
+            .iter()
+            .filter(|arg| arg.kind == ArgKind::Positional)
+            .nth(positional)
+            .map(|arg| arg.ty.choices())
+            .unwrap_or_default()
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        candidates.extend(
+            self.args
+                .iter()
+                .filter(|arg| matches!(arg.kind, ArgKind::Flag | ArgKind::Option))
+                .filter(|arg| !args.contains(&arg.name))
+                .map(|arg| arg.name.to_string()),
+        );
+
+        if let Some(complete) = self.complete {
+            candidates.extend(complete(args));
+        }
+
+        candidates
+    }
+}
+
+/// The input and output of an executed [Command].
+///
+/// The input is the output of the previous command in a pipeline and empty otherwise.
+/// Everything written to the output is piped into the next command,
+/// redirected into a [buffer](crate::control::buffer) or printed to the control.
+///
+/// Long-running commands should regularly call [CommandIo::checkpoint],
+/// so they can be cancelled with Ctrl+C and don't freeze the control.
+pub struct CommandIo {
+    input: String,
+    output: String,
+    token: CancelToken,
+    /// Polls the control of the kernel, which created the command IO.
+    ///
+    /// Modules link their own copy of this crate, so [CommandIo::checkpoint] must not access
+    /// the [CONTROL] of the copy.
+    poll: fn(),
+}
+
+impl CommandIo {
+    /// Create a new command IO with the given input and the token of the command line.
+    pub fn new(input: String, token: CancelToken) -> Self {
+        Self {
+            input,
+            output: String::new(),
+            token,
+            poll: poll_control,
+        }
+    }
+
+    /// Returns the input of the command.
+    pub fn input(&self) -> &str {
+        &self.input
+    }
+
+    /// Returns the output written so far.
+    pub fn output(&self) -> &str {
+        &self.output
+    }
+
+    /// Returns the cancellation token of the command line.
+    pub fn token(&self) -> &CancelToken {
+        &self.token
+    }
+
+    /// Returns `true` if the cancellation of the command line was requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// Let the control handle input and render, then check for cancellation.
+    ///
+    /// Fails with an empty error if the command line was cancelled, which should be returned
+    /// by the command. The cancellation is reported by the control.
+    pub fn checkpoint(&self) -> Result<(), String> {
+        (self.poll)();
+
+        if self.is_cancelled() {
+            return Err(String::new());
+        }
+
+        Ok(())
+    }
+
+    /// Consume the command IO and return its output.
+    pub fn into_output(self) -> String {
+        self.output
+    }
+
+    /// Write formatted text to the output.
+    ///
+    /// Writing to the output never fails, so [write!] and [writeln!] can be used without
+    /// handling the result.
+    pub fn write_fmt(&mut self, args: Arguments) {
+        let _ = self.output.write_fmt(args);
+    }
+}
+
+impl Write for CommandIo {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.output.push_str(s);
+
+        Ok(())
+    }
+}
+
+/// Poll the global [CONTROL] of the kernel on a [CommandIo::checkpoint].
+fn poll_control() {
+    CONTROL.get().poll();
+}
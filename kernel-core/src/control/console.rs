@@ -0,0 +1,490 @@
+use crate::control::app::{App, AppCommand};
+use crate::control::job::CancelToken;
+use crate::control::line::LineEditor;
+use crate::control::scrollback::Scrollback;
+use crate::control::serial_input;
+use crate::control::{Control, InnerControl};
+use crate::process;
+use crate::process::table::PROCESSES;
+use crate::serial_print;
+use crate::terminal::TerminalBox;
+use alloc::boxed::Box;
+use alloc::format;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::Write;
+use pc_keyboard::{DecodedKey, KeyCode};
+use ustyle::{Span, Style};
+
+/// A virtual console of the [Control] with its own scrollback, command line and app.
+pub struct Console {
+    lines: Scrollback,
+    string_buf: String, // temporary buffer for yet-to-be-parsed strings
+    line: LineEditor,
+    scroll_offset: usize,
+    /// The number of lines shown at once, including the command line.
+    page: usize,
+    search: Option<Search>,
+    app: Option<Box<dyn App>>,
+    /// The token of the command line entered in this console, while it's running.
+    running: Option<CancelToken>,
+}
+
+/// The incremental search through the scrollback of a [Console].
+struct Search {
+    /// The search prompt shown instead of the command line, `/` followed by the pattern.
+    prompt: String,
+    pattern: Vec<char>,
+    /// If the pattern is still being typed, otherwise the matches are browsed with `n` and `N`.
+    typing: bool,
+    /// The index of the line with the current match.
+    current: Option<usize>,
+}
+
+impl Console {
+    const STRING_BUF_CAPACITY: usize = 256;
+    const PARSE_CAPACITY: usize = 4;
+    const EXPANDED_TAB: &'static str = "    ";
+
+    /// Create a new, empty console with the given scrollback limit,
+    /// which shows `page` lines at once.
+    pub fn new(limit: usize, page: usize) -> Self {
+        Self {
+            lines: Scrollback::new(limit),
+            string_buf: String::with_capacity(Self::STRING_BUF_CAPACITY),
+            line: LineEditor::new(),
+            scroll_offset: 0,
+            page: page.max(1),
+            search: None,
+            app: None,
+            running: None,
+        }
+    }
+
+    /// Set the maximum number of scrollback lines, dropping the oldest lines if there are more.
+    pub fn set_scrollback_limit(&mut self, limit: usize) {
+        let dropped = self.lines.set_limit(limit);
+
+        self.dropped(dropped);
+    }
+
+    /// Set the [App] of this console.
+    ///
+    /// If there already was another app active, it will be exited.
+    pub fn set_app(&mut self, app: Box<dyn App>) {
+        self.app.replace(app).map(|mut app| app.exit());
+    }
+
+    /// Returns the running [App] of this console.
+    pub fn app_mut(&mut self) -> Option<&mut dyn App> {
+        self.app.as_deref_mut()
+    }
+
+    /// Clears the scrollback of this console.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+        self.string_buf.clear();
+        self.scroll_offset = 0;
+        self.search = None;
+    }
+
+    /// Set the token of the running command line entered in this console, cancelled by Ctrl+C.
+    pub(super) fn set_running(&mut self, token: Option<CancelToken>) {
+        self.running = token;
+    }
+
+    /// Handle a key pressed while this console, which has the given index, is active.
+    pub(super) fn handle_key(&mut self, key: DecodedKey, control: &Control, index: usize) {
+        if let Some(app) = &mut self.app {
+            let command = app.handle_input(key);
+
+            self.handle_command(command);
+
+            return;
+        }
+
+        if self.handle_search_key(key) {
+            return;
+        }
+
+        match key {
+            DecodedKey::Unicode(ch) => match ch {
+                // Ctrl+C => kill foreground job, cancel running command or discard command
+                '\u{3}' => {
+                    if let Some(pid) = process::foreground() {
+                        PROCESSES.run(|table| table.kill(pid));
+
+                        self.string_buf
+                            .push_str(&format!("^C\nKilled process with PID {pid}.\n"));
+                    } else if let Some(token) = self.running.take() {
+                        token.cancel();
+                        self.string_buf.push_str("^C\n");
+                    } else {
+                        self.line.clear();
+                        self.string_buf.push_str("^C\n");
+                    }
+                }
+
+                // New line => execute
+                '\n' => {
+                    let command = self.line.submit();
+
+                    self.string_buf
+                        .push_str(&format!("{} {command}\n", InnerControl::COMMAND_PREFIX));
+
+                    control.queue.push((index, command));
+                }
+
+                // Slash on an empty line => search the scrollback
+                '/' if self.line.line().is_empty() => {
+                    self.search = Some(Search {
+                        prompt: String::from("/"),
+                        pattern: Vec::new(),
+                        typing: true,
+                        current: None,
+                    });
+                }
+
+                // Tab => complete the word before the cursor
+                '\t' => self.complete(control),
+
+                // Backspace => delete character before the cursor
+                '\x08' => self.line.backspace(),
+
+                // Delete => delete character under the cursor
+                '\x7f' => self.line.delete(),
+
+                // Ctrl+W => delete word before the cursor
+                '\u{17}' => self.line.kill_word(),
+
+                // Ctrl+U => delete everything before the cursor
+                '\u{15}' => self.line.kill_line(),
+
+                // Else => insert at the cursor
+                _ => self.line.insert(ch),
+            },
+
+            DecodedKey::RawKey(code) => match code {
+                KeyCode::ArrowLeft => self.line.left(),
+                KeyCode::ArrowRight => self.line.right(),
+                // Home and End on an empty line => jump to the top or bottom of the scrollback
+                KeyCode::Home if self.line.line().is_empty() => self.scroll_up(self.lines.len()),
+                KeyCode::End if self.line.line().is_empty() => self.scroll_offset = 0,
+
+                KeyCode::Home => self.line.home(),
+                KeyCode::End => self.line.end(),
+                KeyCode::Delete => self.line.delete(),
+                KeyCode::ArrowUp => self.line.history_prev(),
+                KeyCode::ArrowDown => self.line.history_next(),
+
+                // Scroll up or down by a page
+                KeyCode::PageUp => self.scroll_up(self.page.saturating_sub(1).max(1)),
+                KeyCode::PageDown => self.scroll_down(self.page.saturating_sub(1).max(1)),
+
+                // Else => do nothing
+                _ => (),
+            },
+        }
+    }
+
+    /// Returns the command line and the cursor position in chars,
+    /// unless an app or the search is shown instead.
+    pub(super) fn command_line(&self) -> Option<(String, usize)> {
+        if self.app.is_some() || self.search.is_some() {
+            return None;
+        }
+
+        Some((self.line.line().to_string(), self.line.cursor()))
+    }
+
+    /// Echo the command line to the serial port after the given key was handled,
+    /// given the command line before.
+    ///
+    /// The line is redrawn whenever it changed, so edits, history and completion are shown.
+    pub(super) fn echo(&self, key: DecodedKey, previous: Option<(String, usize)>) {
+        let Some(previous) = previous else {
+            return;
+        };
+
+        match key {
+            DecodedKey::Unicode('\n') => serial_input::print("\n"),
+            DecodedKey::Unicode('\u{3}') => serial_input::print("^C\n"),
+            _ => {
+                let Some((line, cursor)) = self.command_line() else {
+                    return;
+                };
+
+                if previous.0 == line && previous.1 == cursor {
+                    return;
+                }
+
+                // Return to the start, clear the line and move the cursor back from the end
+                serial_print!("\r\x1b[K{} {line}", InnerControl::COMMAND_PREFIX);
+
+                let back = line.chars().count() - cursor;
+
+                if back > 0 {
+                    serial_print!("\x1b[{back}D");
+                }
+            }
+        }
+    }
+
+    /// Handle a key while searching the scrollback.
+    ///
+    /// Returns `false` if the key is not consumed by the search and must be handled normally.
+    fn handle_search_key(&mut self, key: DecodedKey) -> bool {
+        let Some(search) = &mut self.search else {
+            return false;
+        };
+
+        match (search.typing, key) {
+            // Esc or Ctrl+C => stop searching
+            (_, DecodedKey::Unicode('\u{1b}' | '\u{3}')) => self.search = None,
+
+            // New line => browse the matches
+            (true, DecodedKey::Unicode('\n')) => search.typing = false,
+
+            // Backspace => delete the last char of the pattern and search from the newest line
+            (true, DecodedKey::Unicode('\x08')) => {
+                if search.pattern.pop().is_none() {
+                    self.search = None;
+                } else {
+                    search.prompt.pop();
+                    self.find(self.lines.len(), true);
+                }
+            }
+
+            // Else => extend the pattern and search from the current match
+            (true, DecodedKey::Unicode(ch)) if !ch.is_control() => {
+                search.pattern.push(ch);
+                search.prompt.push(ch);
+
+                let start = search.current.unwrap_or(self.lines.len());
+                self.find(start, true);
+            }
+
+            (true, _) => (),
+
+            // n => jump to the next older match
+            (false, DecodedKey::Unicode('n')) => {
+                if let Some(start) = search.current.and_then(|current| current.checked_sub(1)) {
+                    self.find(start, true);
+                }
+            }
+
+            // N => jump to the next newer match
+            (false, DecodedKey::Unicode('N')) => {
+                if let Some(current) = search.current {
+                    self.find(current + 1, false);
+                }
+            }
+
+            // Slash => type a new pattern
+            (false, DecodedKey::Unicode('/')) => {
+                search.prompt.truncate(1);
+                search.pattern.clear();
+                search.typing = true;
+            }
+
+            // Scrolling keeps browsing the matches
+            (false, DecodedKey::RawKey(_)) => return false,
+
+            // Else => stop searching and handle the key normally
+            (false, DecodedKey::Unicode(_)) => {
+                self.search = None;
+                return false;
+            }
+        }
+
+        true
+    }
+
+    /// Find the next line matching the search pattern from the given line and show it.
+    ///
+    /// The current match is kept if nothing is found while browsing.
+    fn find(&mut self, start: usize, backwards: bool) {
+        let Some(search) = &mut self.search else {
+            return;
+        };
+
+        let found = self.lines.find(&search.pattern, start, backwards);
+
+        if found.is_none() && !search.typing {
+            return;
+        }
+
+        search.current = found;
+
+        if let Some(index) = found {
+            self.show(index);
+        }
+    }
+
+    /// Scroll, so the line with the given index is the topmost visible line if possible.
+    fn show(&mut self, index: usize) {
+        self.scroll_offset = self.max_scroll().saturating_sub(index);
+    }
+
+    fn scroll_up(&mut self, lines: usize) {
+        self.scroll_offset = (self.scroll_offset + lines).min(self.max_scroll());
+    }
+
+    fn scroll_down(&mut self, lines: usize) {
+        self.scroll_offset = self.scroll_offset.saturating_sub(lines);
+    }
+
+    /// Returns the scroll offset showing the oldest line at the top.
+    fn max_scroll(&self) -> usize {
+        // + command line
+        (self.lines.len() + 1).saturating_sub(self.page)
+    }
+
+    /// Update the current match and the scroll offset after the oldest lines were dropped.
+    fn dropped(&mut self, lines: usize) {
+        if lines == 0 {
+            return;
+        }
+
+        if let Some(search) = &mut self.search {
+            search.current = search
+                .current
+                .and_then(|current| current.checked_sub(lines));
+        }
+
+        self.scroll_offset = self.scroll_offset.min(self.max_scroll());
+    }
+
+    /// Complete the word before the cursor.
+    ///
+    /// A single candidate is inserted, multiple candidates are completed to their common prefix.
+    /// If that does not extend the word, the candidates are listed instead.
+    fn complete(&mut self, control: &Control) {
+        let candidates = control.complete(self.line.before_cursor());
+
+        match candidates.as_slice() {
+            [] => (),
+            [candidate] => {
+                self.line.complete(candidate);
+                self.line.insert(' ');
+            }
+            [first, rest @ ..] => {
+                let prefix = rest.iter().fold(first.as_str(), |prefix, candidate| {
+                    let len = prefix
+                        .char_indices()
+                        .zip(candidate.chars())
+                        .find(|((_, a), b)| a != b)
+                        .map_or(prefix.len().min(candidate.len()), |((index, _), _)| index);
+
+                    &prefix[..len]
+                });
+
+                if prefix.len() > self.line.word().len() {
+                    self.line.complete(prefix);
+                } else {
+                    self.string_buf.push_str(&format!(
+                        "{} {}\n{}\n",
+                        InnerControl::COMMAND_PREFIX,
+                        self.line.line(),
+                        candidates.join("  ")
+                    ));
+                }
+            }
+        }
+    }
+
+    /// Parse the written but not yet parsed text into lines of the given maximum width.
+    pub(super) fn flush(&mut self, max_width: usize) {
+        if self.string_buf.is_empty() {
+            return;
+        }
+
+        let string = self.string_buf.drain(..).collect::<String>();
+
+        let spans =
+            Span::decode_capacity(&string, Self::PARSE_CAPACITY).expect("Failed to parse spans");
+
+        let mut current = Vec::with_capacity(max_width);
+        let mut dropped = 0;
+
+        for span in spans {
+            for ch in span.text.chars() {
+                if ch == '\n' {
+                    dropped += self.lines.push(core::mem::take(&mut current)) as usize;
+                } else {
+                    current.push((ch, span.style));
+
+                    if current.len() == max_width {
+                        dropped += self.lines.push(core::mem::take(&mut current)) as usize;
+                    }
+                }
+            }
+        }
+
+        if !current.is_empty() {
+            dropped += self.lines.push(current) as usize;
+        }
+
+        self.dropped(dropped);
+    }
+
+    /// Returns the widget rendering the scrollback and the command line of this console.
+    pub(super) fn terminal_box(&self, default: Style) -> TerminalBox<'_> {
+        match &self.search {
+            Some(search) => TerminalBox::new(
+                &self.lines,
+                &search.prompt,
+                search.prompt.chars().count(),
+                default,
+                self.scroll_offset,
+            )
+            .search(&search.pattern, search.current),
+            None => TerminalBox::new(
+                &self.lines,
+                self.line.line(),
+                self.line.cursor(),
+                default,
+                self.scroll_offset,
+            ),
+        }
+    }
+
+    /// Handle the command returned by the [App] of this console.
+    pub(super) fn handle_command(&mut self, command: AppCommand) {
+        match command {
+            AppCommand::SetApp(app) => {
+                self.app.replace(app).unwrap().exit();
+            }
+
+            AppCommand::Exit(msg) => {
+                self.app.take().unwrap().exit();
+
+                if let Some(msg) = msg {
+                    self.string_buf.push_str(&msg);
+                }
+            }
+
+            AppCommand::Multiple(commands) => {
+                for command in commands {
+                    self.handle_command(command);
+                }
+            }
+
+            AppCommand::Continue => (),
+        }
+    }
+}
+
+impl Write for Console {
+    fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        for ch in s.chars() {
+            if ch == '\t' {
+                self.string_buf.push_str(Self::EXPANDED_TAB);
+            } else {
+                self.string_buf.push(ch);
+            }
+        }
+
+        Ok(())
+    }
+}
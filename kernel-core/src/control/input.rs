@@ -1,5 +1,6 @@
 use crate::serial_println;
 use crate::sync::init::InitData;
+use core::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use crossbeam_queue::ArrayQueue;
 use pc_keyboard::DecodedKey;
 
@@ -10,15 +11,37 @@ pub static INPUT: InitData<InputControl> = InitData::uninit();
 #[derive(Debug)]
 pub struct InputControl {
     keys: ArrayQueue<DecodedKey>,
+    /// The index of the requested virtual console or [Self::NO_CONSOLE].
+    console: AtomicUsize,
+    /// If a key was received from the serial port, so the command line is echoed there.
+    serial: AtomicBool,
 }
 
 impl InputControl {
     const KEY_BUF_SIZE: usize = 64;
+    const NO_CONSOLE: usize = usize::MAX;
 
     /// Creates a new input control instance.
     pub fn new() -> Self {
         Self {
             keys: ArrayQueue::new(Self::KEY_BUF_SIZE),
+            console: AtomicUsize::new(Self::NO_CONSOLE),
+            serial: AtomicBool::new(false),
+        }
+    }
+
+    /// Request to switch to the virtual console with the given index, e.g. on Alt+F1.
+    ///
+    /// This is safe to call in interrupt context.
+    pub fn request_console(&self, index: usize) {
+        self.console.store(index, Ordering::Relaxed);
+    }
+
+    /// Take the index of the requested virtual console, if any.
+    pub fn take_console(&self) -> Option<usize> {
+        match self.console.swap(Self::NO_CONSOLE, Ordering::Relaxed) {
+            Self::NO_CONSOLE => None,
+            index => Some(index),
         }
     }
 
@@ -30,6 +53,17 @@ impl InputControl {
         });
     }
 
+    /// Push a key received from the serial port to the queue.
+    pub fn push_serial(&self, key: DecodedKey) {
+        self.serial.store(true, Ordering::Relaxed);
+        self.push(key);
+    }
+
+    /// Returns `true` if a key was ever received from the serial port.
+    pub fn has_serial(&self) -> bool {
+        self.serial.load(Ordering::Relaxed)
+    }
+
     /// Pop the next key from the queue.
     pub fn pop(&self) -> Option<DecodedKey> {
         self.keys.pop()
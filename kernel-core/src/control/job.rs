@@ -0,0 +1,153 @@
+use alloc::string::String;
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::sync::atomic::{AtomicBool, Ordering};
+
+/// A token for the cooperative cancellation of a running command line.
+///
+/// Every command of the command line receives a clone of the token through its
+/// [CommandIo](crate::control::command::CommandIo) and should regularly check it.
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    /// Create a new token, which is not cancelled.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request the cancellation of the command line.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Returns `true` if the cancellation was requested.
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// The state of a [Job].
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum JobState {
+    /// The job waits for the previous jobs to finish.
+    Queued,
+    /// The job is running and paused whenever its commands let the control update.
+    Running,
+    /// The cancellation of the job was requested.
+    Cancelled,
+}
+
+impl Display for JobState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            JobState::Queued => write!(f, "queued"),
+            JobState::Running => write!(f, "running"),
+            JobState::Cancelled => write!(f, "cancelled"),
+        }
+    }
+}
+
+/// A command line running in the background, started with a trailing `&`.
+#[derive(Clone, Debug)]
+pub struct Job {
+    /// The ID of the job, shown as `[id]` in the reports.
+    pub id: usize,
+    /// The command line without the trailing `&`.
+    pub line: String,
+    /// The index of the console the job was started in, which receives its output.
+    pub console: usize,
+    /// The token cancelling the job.
+    pub token: CancelToken,
+    /// The state of the job.
+    pub state: JobState,
+}
+
+/// The background jobs of the [Control](super::Control), run one after another.
+pub struct JobTable {
+    jobs: Vec<Job>,
+    next_id: usize,
+}
+
+impl JobTable {
+    /// Create a new, empty job table.
+    pub const fn new() -> Self {
+        Self {
+            jobs: Vec::new(),
+            next_id: 1,
+        }
+    }
+
+    /// Queue the given command line, started in the given console, and return the job ID.
+    pub fn spawn(&mut self, line: String, console: usize) -> usize {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.jobs.push(Job {
+            id,
+            line,
+            console,
+            token: CancelToken::new(),
+            state: JobState::Queued,
+        });
+
+        id
+    }
+
+    /// Mark the oldest queued job as running and return a copy of it.
+    ///
+    /// Returns [None] if there is no queued job or another job is still running.
+    pub fn start(&mut self) -> Option<Job> {
+        if self.is_running() {
+            return None;
+        }
+
+        let job = self.jobs.first_mut()?;
+        job.state = JobState::Running;
+
+        Some(job.clone())
+    }
+
+    /// Returns `true` if a job is running, even if its cancellation was already requested.
+    pub fn is_running(&self) -> bool {
+        self.jobs.iter().any(|job| job.state != JobState::Queued)
+    }
+
+    /// Remove the job with the given ID after it finished.
+    pub fn finish(&mut self, id: usize) -> Option<Job> {
+        let index = self.jobs.iter().position(|job| job.id == id)?;
+
+        Some(self.jobs.remove(index))
+    }
+
+    /// Request the cancellation of the job with the given ID.
+    ///
+    /// Queued jobs are removed right away. Returns `false` if there is no job with the given ID.
+    pub fn cancel(&mut self, id: usize) -> bool {
+        let Some(job) = self.jobs.iter_mut().find(|job| job.id == id) else {
+            return false;
+        };
+
+        job.token.cancel();
+
+        if job.state == JobState::Queued {
+            self.finish(id);
+        } else {
+            job.state = JobState::Cancelled;
+        }
+
+        true
+    }
+
+    /// Returns an iterator over all jobs in the order they were started.
+    pub fn iter(&self) -> impl Iterator<Item = &Job> {
+        self.jobs.iter()
+    }
+}
+
+impl Default for JobTable {
+    fn default() -> Self {
+        Self::new()
+    }
+}
@@ -0,0 +1,188 @@
+use alloc::collections::VecDeque;
+use alloc::string::String;
+
+/// An editable command line with a cursor and a history of submitted lines.
+pub struct LineEditor {
+    line: String,
+    /// The byte offset of the cursor, always on a char boundary.
+    cursor: usize,
+    history: VecDeque<String>,
+    /// The history entry currently shown, counted from the newest entry.
+    position: Option<usize>,
+    /// The unsubmitted line, restored when moving past the newest history entry.
+    draft: String,
+}
+
+impl LineEditor {
+    const LINE_CAPACITY: usize = 16;
+    const HISTORY_CAPACITY: usize = 64;
+
+    /// Create a new, empty line editor.
+    pub fn new() -> Self {
+        Self {
+            line: String::with_capacity(Self::LINE_CAPACITY),
+            cursor: 0,
+            history: VecDeque::with_capacity(Self::HISTORY_CAPACITY),
+            position: None,
+            draft: String::new(),
+        }
+    }
+
+    /// Returns the current line.
+    pub fn line(&self) -> &str {
+        &self.line
+    }
+
+    /// Returns the cursor position in chars.
+    pub fn cursor(&self) -> usize {
+        self.line[..self.cursor].chars().count()
+    }
+
+    /// Returns the part of the line before the cursor.
+    pub fn before_cursor(&self) -> &str {
+        &self.line[..self.cursor]
+    }
+
+    /// Returns the part of the word before the cursor.
+    pub fn word(&self) -> &str {
+        self.before_cursor()
+            .rsplit(char::is_whitespace)
+            .next()
+            .unwrap_or_default()
+    }
+
+    /// Replace the part of the word before the cursor with the given completion.
+    pub fn complete(&mut self, completion: &str) {
+        let start = self.cursor - self.word().len();
+
+        self.line.replace_range(start..self.cursor, completion);
+        self.cursor = start + completion.len();
+    }
+
+    /// Insert a char at the cursor.
+    pub fn insert(&mut self, ch: char) {
+        self.line.insert(self.cursor, ch);
+        self.cursor += ch.len_utf8();
+    }
+
+    /// Delete the char before the cursor.
+    pub fn backspace(&mut self) {
+        if let Some(ch) = self.line[..self.cursor].chars().next_back() {
+            self.cursor -= ch.len_utf8();
+            self.line.remove(self.cursor);
+        }
+    }
+
+    /// Delete the char under the cursor.
+    pub fn delete(&mut self) {
+        if self.cursor < self.line.len() {
+            self.line.remove(self.cursor);
+        }
+    }
+
+    /// Move the cursor one char to the left.
+    pub fn left(&mut self) {
+        if let Some(ch) = self.line[..self.cursor].chars().next_back() {
+            self.cursor -= ch.len_utf8();
+        }
+    }
+
+    /// Move the cursor one char to the right.
+    pub fn right(&mut self) {
+        if let Some(ch) = self.line[self.cursor..].chars().next() {
+            self.cursor += ch.len_utf8();
+        }
+    }
+
+    /// Move the cursor to the start of the line.
+    pub fn home(&mut self) {
+        self.cursor = 0;
+    }
+
+    /// Move the cursor to the end of the line.
+    pub fn end(&mut self) {
+        self.cursor = self.line.len();
+    }
+
+    /// Delete the word before the cursor, including the whitespace following it.
+    pub fn kill_word(&mut self) {
+        let before = self.line[..self.cursor].trim_end();
+        let word = before
+            .rsplit(char::is_whitespace)
+            .next()
+            .unwrap_or_default();
+        let start = before.len() - word.len();
+
+        self.line.replace_range(start..self.cursor, "");
+        self.cursor = start;
+    }
+
+    /// Delete everything before the cursor.
+    pub fn kill_line(&mut self) {
+        self.line.replace_range(..self.cursor, "");
+        self.cursor = 0;
+    }
+
+    /// Discard the current line without adding it to the history.
+    pub fn clear(&mut self) {
+        self.line.clear();
+        self.cursor = 0;
+        self.position = None;
+    }
+
+    /// Take the current line and add it to the history.
+    ///
+    /// Empty lines and repetitions of the newest entry are not added.
+    pub fn submit(&mut self) -> String {
+        let line = core::mem::take(&mut self.line);
+
+        self.cursor = 0;
+        self.position = None;
+
+        if !line.trim().is_empty() && self.history.back() != Some(&line) {
+            if self.history.len() == Self::HISTORY_CAPACITY {
+                self.history.pop_front();
+            }
+
+            self.history.push_back(line.clone());
+        }
+
+        line
+    }
+
+    /// Replace the line with the previous (older) history entry.
+    pub fn history_prev(&mut self) {
+        let position = self.position.map_or(0, |position| position + 1);
+
+        if position >= self.history.len() {
+            return;
+        }
+
+        if self.position.is_none() {
+            self.draft = core::mem::take(&mut self.line);
+        }
+
+        self.show(position);
+    }
+
+    /// Replace the line with the next (newer) history entry or the unsubmitted line.
+    pub fn history_next(&mut self) {
+        match self.position {
+            None => (),
+            Some(0) => {
+                self.position = None;
+                self.line = core::mem::take(&mut self.draft);
+                self.cursor = self.line.len();
+            }
+            Some(position) => self.show(position - 1),
+        }
+    }
+
+    /// Show the history entry at the given position, counted from the newest entry.
+    fn show(&mut self, position: usize) {
+        self.position = Some(position);
+        self.line
+            .clone_from(&self.history[self.history.len() - 1 - position]);
+        self.cursor = self.line.len();
+    }
+}
@@ -1,26 +1,33 @@
 use crate::collections::FastMap;
 use crate::control::app::{App, AppCommand};
-use crate::control::command::{Command, builtin};
+use crate::control::command::{Command, CommandIo, builtin};
+use crate::control::console::Console;
 use crate::control::display::{DISPLAY, Display};
 use crate::control::input::{INPUT, InputControl};
+use crate::control::job::{CancelToken, Job, JobTable};
+use crate::control::pipeline::{self, Pipeline};
+use crate::control::scrollback::Scrollback;
+use crate::control::timer::Timer;
 use crate::sync::init::InitData;
 use crate::sync::mutex::Mutex;
-use crate::terminal::TerminalBox;
+use crate::sync::rwlock::RwLock;
 use crate::wrapper::SendSyncWrapper;
+use crate::{api, process};
 use alloc::boxed::Box;
+use alloc::collections::BTreeMap;
 use alloc::format;
 use alloc::string::{String, ToString};
 use alloc::vec::Vec;
 use core::fmt::Write;
+use core::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use crossbeam_queue::SegQueue;
 use embedded_graphics::pixelcolor::Rgb888;
 use mousefood::{EmbeddedBackend, EmbeddedBackendConfig, TerminalAlignment};
-use pc_keyboard::{DecodedKey, KeyCode};
 use ratatui::Terminal;
 use ratatui::backend::Backend;
 use ratatui::layout::Rect;
 use ratatui::widgets::{Block, BorderType, Borders};
-use ustyle::{Attributes, Color, Span, Style};
+use ustyle::{Attributes, Color, Style};
 
 /// Contains the [Display] type.
 pub mod display;
@@ -28,12 +35,40 @@ pub mod display;
 /// Contains the [InputControl] struct.
 pub mod input;
 
+/// Contains the [SerialDecoder](serial_input::SerialDecoder) of the input from the serial port
+/// and the output of the control to it.
+pub mod serial_input;
+
+/// Contains the [LineEditor] of the command line.
+pub mod line;
+
+/// Contains the virtual [Console]s of the control.
+pub mod console;
+
+/// Contains the [Scrollback](scrollback::Scrollback) ring buffer of the consoles.
+pub mod scrollback;
+
 /// Provides control application structures.
 pub mod app;
 
 /// Contains the [Command] struct and related features.
 pub mod command;
 
+/// Contains the [Pipeline] parser for piped and redirected command lines.
+pub mod pipeline;
+
+/// Contains the named buffers command output can be redirected into.
+pub mod buffer;
+
+/// Contains the background [Job]s and the [CancelToken] of command lines.
+pub mod job;
+
+/// Contains the [Timer]s run periodically by the control.
+pub mod timer;
+
+/// Contains the [Script](script::Script) interpreter of the control.
+pub mod script;
+
 /// Global [Control] instance.
 pub static CONTROL: InitData<Control> = InitData::uninit();
 
@@ -44,6 +79,8 @@ static mut INIT: bool = false;
 /// # Safety
 /// This must only be called once, before any global [CONTROL] use.
 pub unsafe fn init() {
+    app::register("breakout", || Box::new(app::breakout::BreakoutApp::new()));
+
     unsafe {
         INPUT.init(InputControl::new());
         CONTROL.init(Control::new());
@@ -61,13 +98,27 @@ pub fn is_init() -> bool {
 ///
 /// Similar to the shell in Linux, but always active and globally reachable.
 pub struct Control {
-    queue: SegQueue<String>,
-    registry: FastMap<&'static str, Command>,
+    /// The submitted command lines with the index of the console they were entered in.
+    queue: SegQueue<(usize, String)>,
+    registry: RwLock<FastMap<&'static str, Command>>,
+    aliases: RwLock<BTreeMap<String, String>>,
+    jobs: Mutex<JobTable>,
+    timers: Mutex<Vec<Timer>>,
+    /// The cancellation token of the running command line.
+    token: Mutex<CancelToken>,
+    /// If queued command lines are being executed.
+    executing: AtomicBool,
+    /// The names of the commands on the stack, innermost last.
+    running: Mutex<Vec<&'static str>>,
+    /// The tick of the last [Control::poll].
+    last_poll: AtomicU64,
     inner: Mutex<InnerControl>,
 }
 
 impl Control {
     const MAX_EXECUTED_COMMANDS: u8 = 4;
+    /// The minimum time between two updates of the control by [Control::poll].
+    const POLL_MILLIS: u64 = 50;
 
     /// Create a new control instance.
     ///
@@ -76,31 +127,160 @@ impl Control {
     pub unsafe fn new() -> Self {
         Self {
             queue: SegQueue::new(),
-            registry: FastMap::from_iter(
+            registry: RwLock::new(FastMap::from_iter(
                 builtin::COMMANDS
                     .iter()
                     .map(|command| (command.name, *command)),
-            ),
+            )),
+            aliases: RwLock::new(BTreeMap::new()),
+            jobs: Mutex::new(JobTable::new()),
+            timers: Mutex::new(Vec::new()),
+            token: Mutex::new(CancelToken::new()),
+            executing: AtomicBool::new(false),
+            running: Mutex::new(Vec::new()),
+            last_poll: AtomicU64::new(0),
             inner: Mutex::new(unsafe { InnerControl::new() }),
         }
     }
 
     /// Registers a command for the control.
     ///
-    /// Can only be used, by obtaining an **unsafe** mutable reference to the global [CONTROL].
-    pub fn register(&mut self, command: Command) {
-        self.registry.insert(command.name, command);
+    /// Returns `false` if a command with the same name is already registered.
+    pub fn register(&self, command: Command) -> bool {
+        self.registry.run_mut(|registry| {
+            if registry.contains_key(command.name) {
+                return false;
+            }
+
+            registry.insert(command.name, command);
+
+            true
+        })
+    }
+
+    /// Unregisters the command with the given name and returns it.
+    pub fn unregister(&self, name: &str) -> Option<Command> {
+        self.registry.run_mut(|registry| registry.remove(name))
+    }
+
+    /// Returns if the command with the given name is currently executing,
+    /// e.g. further down the stack of a command that polls the control.
+    pub fn is_running(&self, name: &str) -> bool {
+        self.running.run(|running| running.contains(&name))
+    }
+
+    /// Defines an alias, which is replaced by the given command line when used as command name.
+    ///
+    /// Returns `false` if the name is empty or contains whitespace, `|`, `>` or `=`.
+    pub fn set_alias(&self, name: &str, line: &str) -> bool {
+        if name.is_empty() || name.contains(|ch: char| ch.is_whitespace() || "|>=".contains(ch)) {
+            return false;
+        }
+
+        self.aliases
+            .run_mut(|aliases| aliases.insert(name.to_string(), line.to_string()));
+
+        true
+    }
+
+    /// Removes the alias with the given name.
+    ///
+    /// Returns `false` if there is no alias with the given name.
+    pub fn remove_alias(&self, name: &str) -> bool {
+        self.aliases
+            .run_mut(|aliases| aliases.remove(name).is_some())
+    }
+
+    /// Returns the command line of the alias with the given name.
+    pub fn alias(&self, name: &str) -> Option<String> {
+        self.aliases.run(|aliases| aliases.get(name).cloned())
+    }
+
+    /// Returns all aliases with their command lines in alphabetical order.
+    pub fn aliases(&self) -> Vec<(String, String)> {
+        self.aliases.run(|aliases| {
+            aliases
+                .iter()
+                .map(|(name, line)| (name.clone(), line.clone()))
+                .collect()
+        })
+    }
+
+    /// Returns copies of all background jobs in the order they were started.
+    pub fn jobs(&self) -> Vec<Job> {
+        self.jobs.run(|jobs| jobs.iter().cloned().collect())
+    }
+
+    /// Request the cancellation of the background job with the given ID.
+    ///
+    /// Returns `false` if there is no job with the given ID.
+    pub fn cancel_job(&self, id: usize) -> bool {
+        self.jobs.run(|jobs| jobs.cancel(id))
+    }
+
+    /// Add a [Timer] running the given function every `millis` milliseconds,
+    /// starting with the next update of the control.
+    ///
+    /// The output written by the function goes to the console of the running command.
+    /// The timer is removed once the token is cancelled or the function returns `false`.
+    pub fn add_timer(
+        &self,
+        millis: u64,
+        token: CancelToken,
+        func: impl FnMut(&Control) -> bool + Send + 'static,
+    ) {
+        let process = api::process();
+        let console = self.run(|inner| inner.output);
+
+        self.timers.run(|timers| {
+            timers.push(Timer {
+                interval: (millis / process.tick_millis()).max(1),
+                next: process.ticks(),
+                console,
+                token,
+                func: Box::new(func),
+            })
+        });
     }
 
     /// Update the control.
     pub fn update(&self) {
+        self.refresh();
+
+        self.execute(Self::MAX_EXECUTED_COMMANDS);
+        self.run_timers();
+        self.run_job();
+    }
+
+    /// Let the control handle input and render while a command is running.
+    ///
+    /// Called by [CommandIo::checkpoint], but does nothing if the last update was less than
+    /// [Self::POLL_MILLIS] ago. While a background job is running,
+    /// the command lines entered in the meantime are executed as well.
+    pub fn poll(&self) {
+        let process = api::process();
+        let now = process.ticks();
+        let last = self.last_poll.load(Ordering::Relaxed);
+
+        if now.saturating_sub(last) * process.tick_millis() < Self::POLL_MILLIS {
+            return;
+        }
+
+        self.last_poll.store(now, Ordering::Relaxed);
+
+        self.refresh();
+
+        if self.jobs.run(|jobs| jobs.is_running()) {
+            self.execute(Self::MAX_EXECUTED_COMMANDS);
+        }
+    }
+
+    /// Handle the input and render the active console.
+    fn refresh(&self) {
         self.run(|inner| {
-            inner.handle_input(&self.queue);
+            inner.handle_input(self);
             inner.render();
         });
-
-        self.execute(Self::MAX_EXECUTED_COMMANDS)
-            .unwrap_or_else(|err| log::error!("{err}"));
     }
 
     /// Lock the [InnerControl] and run the specified closure on it, then unlock it at last.
@@ -109,54 +289,322 @@ impl Control {
     }
 
     /// Execute all the commands in queue, but a maximum of `max` times.
-    pub fn execute(&self, max: u8) -> Result<(), String> {
+    ///
+    /// The output and errors of each command line are written to the console it was entered in.
+    /// Commands stay queued while there is a foreground job.
+    /// Command lines ending with `&` are queued as background jobs instead.
+    pub fn execute(&self, max: u8) {
+        if process::foreground().is_some() || self.executing.swap(true, Ordering::Acquire) {
+            return;
+        }
+
         let mut i = 0;
 
-        while let Some(query) = self.queue.pop()
-            && i <= max
+        while i < max
+            && let Some((console, query)) = self.queue.pop()
         {
-            let (name, args) = query.trim().split_once(' ').unwrap_or((query.as_str(), ""));
+            match pipeline::background(&query) {
+                (line, true) => {
+                    let id = self.jobs.run(|jobs| jobs.spawn(line.to_string(), console));
+
+                    self.report(console, &format!("[{id}] {line}\n"));
+                }
+                (line, false) => {
+                    let token = CancelToken::new();
 
-            match name {
-                "help" => self.log_help(),
-                "" => (),
-                _ => {
-                    let command = self.registry.get(name.trim()).ok_or_else(|| {
-                        format!("Command '{query}' not found! Type 'help' for help.")
-                    })?;
+                    // Ctrl+C in the console cancels the command line
+                    self.run(|inner| inner.consoles[console].set_running(Some(token.clone())));
 
-                    (command.run)(args.trim().to_string())?;
+                    self.run_in(console, token, || {
+                        self.run_line(line).unwrap_or_else(|err| log_error(&err))
+                    });
+
+                    self.run(|inner| inner.consoles[console].set_running(None));
                 }
             }
 
             i += 1;
         }
 
-        Ok(())
+        self.executing.store(false, Ordering::Release);
+    }
+
+    /// Run the functions of the due timers and remove the stopped timers.
+    fn run_timers(&self) {
+        let now = api::process().ticks();
+
+        // Taken out, so the functions can add timers themselves
+        let due = self.timers.run(|timers| {
+            timers.retain(|timer| !timer.token.is_cancelled());
+            timers
+                .extract_if(.., |timer| timer.next <= now)
+                .collect::<Vec<_>>()
+        });
+
+        for mut timer in due {
+            let keep = self.run_in(timer.console, timer.token.clone(), || (timer.func)(self));
+
+            if keep && !timer.token.is_cancelled() {
+                timer.next = now.saturating_add(timer.interval);
+
+                self.timers.run(|timers| timers.push(timer));
+            }
+        }
+    }
+
+    /// Run the next queued background job and report its completion in its console.
+    fn run_job(&self) {
+        if process::foreground().is_some() {
+            return;
+        }
+
+        let Some(job) = self.jobs.run(|jobs| jobs.start()) else {
+            return;
+        };
+
+        let result = self.run_in(job.console, job.token.clone(), || {
+            self.run_line(&job.line).inspect_err(|err| log_error(err))
+        });
+
+        self.jobs.run(|jobs| jobs.finish(job.id));
+
+        let state = match result {
+            _ if job.token.is_cancelled() => "Cancelled",
+            Ok(()) => "Done",
+            Err(_) => "Failed",
+        };
+
+        self.report(
+            job.console,
+            &format!("[{}] {state}: {}\n", job.id, job.line),
+        );
+    }
+
+    /// Run the closure with the given console receiving the output and the given token
+    /// passed to the commands, then restore the previous ones.
+    ///
+    /// Command lines are nested while a background job lets the control update.
+    fn run_in<R>(&self, console: usize, token: CancelToken, func: impl FnOnce() -> R) -> R {
+        let output = self.run(|inner| core::mem::replace(&mut inner.output, console));
+        let token = self.token.run(|current| core::mem::replace(current, token));
+
+        let result = func();
+
+        self.token.set(token);
+        self.run(|inner| inner.output = output);
+
+        result
+    }
+
+    /// Print the given report to the console with the given index and the serial port.
+    fn report(&self, console: usize, report: &str) {
+        serial_input::print(report);
+
+        self.run(|inner| inner.consoles[console].write_str(report))
+            .expect("Failed to write report");
     }
 
-    /// Log the help message to the control.
-    pub fn log_help(&self) {
+    /// Run a command line of piped commands and print or redirect the output of the last one.
+    pub fn run_line(&self, line: &str) -> Result<(), String> {
+        let output = self.capture(line)?;
+
+        self.print(&output)
+    }
+
+    /// Run a command line of piped commands and return the output of the last one.
+    ///
+    /// The returned output is empty, if it was redirected into a buffer.
+    pub fn capture(&self, line: &str) -> Result<String, String> {
+        self.pipe(line, String::new(), &[])
+    }
+
+    /// Run a command line of piped commands with the given input to the first one.
+    ///
+    /// The given aliases were already expanded and are not expanded again,
+    /// so aliases can refer to commands with the same name.
+    fn pipe(&self, line: &str, input: String, expanded: &[&str]) -> Result<String, String> {
+        let pipeline = Pipeline::parse(line)?;
+
+        let output = pipeline.commands.iter().try_fold(input, |input, command| {
+            self.run_command(command, input, expanded)
+        })?;
+
+        match pipeline.redirect {
+            Some(redirect) => {
+                buffer::write(redirect.buffer, &output, redirect.append);
+
+                Ok(String::new())
+            }
+            None => Ok(output),
+        }
+    }
+
+    /// Print the given command output to the control and the serial port.
+    pub fn print(&self, output: &str) -> Result<(), String> {
+        if output.is_empty() {
+            return Ok(());
+        }
+
+        serial_input::print(output);
+
+        self.run(|inner| inner.write_str(output))
+            .map_err(|err| err.to_string())
+    }
+
+    /// Run a single command with the given input and return its output.
+    ///
+    /// Aliases are expanded before the command is looked up, unless they were already expanded.
+    fn run_command(&self, query: &str, input: String, expanded: &[&str]) -> Result<String, String> {
+        let (name, args) = query.trim().split_once(' ').unwrap_or((query.trim(), ""));
+
+        if !expanded.contains(&name)
+            && let Some(alias) = self.alias(name)
+        {
+            let mut expanded = expanded.to_vec();
+            expanded.push(name);
+
+            return self.pipe(&format!("{alias} {args}"), input, &expanded);
+        }
+
+        let mut io = CommandIo::new(input, self.token.run(|token| token.clone()));
+
+        match name {
+            "help" => write!(io, "{}", self.help(args)?),
+            "" => (),
+            _ => {
+                // Copied out, since commands may register other commands
+                let command = self
+                    .command(name)
+                    .ok_or_else(|| format!("Command '{query}' not found! Type 'help' for help."))?;
+
+                self.running.run(|running| running.push(command.name));
+                let result = command.execute(args, &mut io);
+                self.running.run(|running| running.pop());
+
+                result?;
+            }
+        }
+
+        Ok(io.into_output())
+    }
+
+    /// Returns a copy of the registered command with the given name.
+    fn command(&self, name: &str) -> Option<Command> {
+        self.registry.run(|registry| registry.get(name).copied())
+    }
+
+    /// Returns the sorted completion candidates for the last word of the given line.
+    ///
+    /// The first word is completed from the registered command names and the aliases,
+    /// the argument of `help` from the command names
+    /// and the following ones by the [Command::candidates] of the command.
+    pub fn complete(&self, line: &str) -> Vec<String> {
+        let mut words = line.split_whitespace().collect::<Vec<_>>();
+
+        // The word being completed is empty after whitespace
+        let word = if line.is_empty() || line.ends_with(char::is_whitespace) {
+            ""
+        } else {
+            words.pop().unwrap_or_default()
+        };
+
+        let candidates = match words.as_slice() {
+            [] => {
+                let mut names = self.registry.run(|registry| {
+                    registry
+                        .keys()
+                        .chain(["help"].iter())
+                        .map(ToString::to_string)
+                        .collect::<Vec<_>>()
+                });
+
+                names.extend(self.aliases().into_iter().map(|(name, _)| name));
+
+                names
+            }
+            ["help"] => self
+                .registry
+                .run(|registry| registry.keys().map(ToString::to_string).collect()),
+            [name, args @ ..] => self
+                .command(name)
+                .map_or_else(Vec::new, |command| command.candidates(args)),
+        };
+
+        let mut candidates = candidates
+            .into_iter()
+            .filter(|candidate| candidate.starts_with(word))
+            .collect::<Vec<_>>();
+
+        candidates.sort_unstable();
+        candidates.dedup();
+
+        candidates
+    }
+
+    /// Returns the help message listing all commands or the detailed help message of the
+    /// command selected by the given arguments, e.g. `time set`.
+    pub fn help(&self, args: &str) -> Result<String, String> {
         const HELP_START: &str = "Control Help:\n\n\
             This is the control, the main interface to the kernel.\n\
             You can think of this as an overarching root shell.\n\
-            Use Arrow Up ↑ and Arrow Down ↓ to scroll through the terminal.\n\n\
+            Use Arrow Left ← and Arrow Right →, Home, End and Delete to edit the command.\n\
+            Use Ctrl+W and Ctrl+U to delete the word or everything before the cursor.\n\
+            Use Tab to complete commands and their arguments.\n\
+            Use `a | b` to pipe the output of a into b and `a > name` or `a >> name` to write\n\
+            or append it to a buffer, which can be printed with `cat name`.\n\
+            Use Arrow Up ↑ and Arrow Down ↓ to browse the command history.\n\
+            Use Page Up and Page Down to scroll through the terminal and Home and End on an\n\
+            empty command line to jump to its top or bottom.\n\
+            Type `/` on an empty command line to search the terminal, Enter to confirm the\n\
+            pattern, n and N to jump to older and newer matches and Esc to stop searching.\n\
+            Use Ctrl+C to terminate the foreground job or cancel the running command.\n\
+            Append `&` to a command line to run it as background job, listed by `jobs`.\n\
+            Use Alt+F1 to Alt+F4 or `console <index>` to switch between the virtual consoles.\n\
+            Use `help <command>` to show the arguments and subcommands of a command.\n\
+            Use `alias name='command args'` to define a shortcut for a command line.\n\n\
             Available Commands:\n\n";
 
-        let mut help = String::with_capacity(self.registry.len() * 32 + HELP_START.len());
+        let args = args.trim();
+
+        if !args.is_empty() {
+            let (name, args) = args.split_once(' ').unwrap_or((args, ""));
 
-        help.push_str(HELP_START);
+            let command = self
+                .command(name)
+                .ok_or_else(|| format!("Command '{name}' not found! Type 'help' for help."))?;
 
-        for command in self.registry.values() {
-            help.push_str(&format!(
-                "{}:\n\
+            return Ok(command.help(args));
+        }
+
+        let help = self.registry.run(|registry| {
+            let mut help = String::with_capacity(registry.len() * 32 + HELP_START.len());
+
+            help.push_str(HELP_START);
+
+            for command in registry.values() {
+                help.push_str(&format!(
+                    "{}:\n\
 \tDescription: {}\n\
 \tUsage: {}\n",
-                command.name, command.description, command.usage
-            ));
-        }
+                    command.name,
+                    command.description,
+                    command.usage(command.name)
+                ));
+            }
+
+            help
+        });
+
+        Ok(help)
+    }
+}
 
-        log::info!("{help}");
+/// Log the error of a command, unless it is empty.
+///
+/// Commands fail with an empty error to only report their failure to scripts.
+pub(crate) fn log_error(err: &str) {
+    if !err.is_empty() {
+        log::error!("{err}");
     }
 }
 
@@ -166,12 +614,13 @@ impl Control {
 /// therefore it's locked behind [Control::run].
 pub struct InnerControl {
     terminal: SendSyncWrapper<Terminal<EmbeddedBackend<'static, Display, Rgb888>>>,
-    lines: Vec<Vec<(char, Style)>>,
-    string_buf: String, // temporary buffer for yet-to-be-parsed strings
-    command: String,
-    scroll_offset: usize,
+    consoles: Vec<Console>,
+    scrollback_limit: usize,
+    /// The console shown on the screen, which receives the input.
+    active: usize,
+    /// The console receiving written text and new apps.
+    output: usize,
     max_width: usize,
-    app: Option<Box<dyn App>>,
 }
 
 impl InnerControl {
@@ -179,12 +628,11 @@ impl InnerControl {
     pub const COMMAND_PREFIX: char = '>';
     /// The command suffix.
     pub const COMMAND_SUFFIX: char = '|';
+    /// The number of virtual consoles, switched with Alt+F1 to Alt+F4.
+    pub const CONSOLES: usize = 4;
+    /// The console receiving log messages and the output written outside of commands.
+    pub const SYSTEM_CONSOLE: usize = 0;
 
-    const LINES_CAPACITY: usize = 128;
-    const STRING_BUF_CAPACITY: usize = 256;
-    const PARSE_CAPACITY: usize = 4;
-    const COMMAND_BUF_CAPACITY: usize = 16;
-    const EXPANDED_TAB: &'static str = "    ";
     const BACKGROUND: Color = Color::DarkerGray;
     const FOREGROUND: Color = Color::BrighterGray;
 
@@ -210,80 +658,100 @@ impl InnerControl {
             )
         };
 
-        let max_width = terminal.size().unwrap().width as usize;
+        let size = terminal.size().unwrap();
+        let max_width = size.width as usize;
+        // The lines inside the border
+        let page = size.height.saturating_sub(2) as usize;
 
         Self {
             terminal,
-            lines: Vec::with_capacity(Self::LINES_CAPACITY),
-            string_buf: String::with_capacity(Self::STRING_BUF_CAPACITY),
-            command: String::with_capacity(Self::COMMAND_BUF_CAPACITY),
-            scroll_offset: 0,
+            consoles: (0..Self::CONSOLES)
+                .map(|_| Console::new(Scrollback::DEFAULT_LIMIT, page))
+                .collect(),
+            scrollback_limit: Scrollback::DEFAULT_LIMIT,
+            active: Self::SYSTEM_CONSOLE,
+            output: Self::SYSTEM_CONSOLE,
             max_width,
-            app: None,
         }
     }
 
-    /// Set the current control [App].
+    /// Returns the index of the console shown on the screen.
+    pub fn active(&self) -> usize {
+        self.active
+    }
+
+    /// Show the console with the given index on the screen.
+    ///
+    /// Returns `false` if there is no console with the given index.
+    pub fn switch(&mut self, index: usize) -> bool {
+        if index >= self.consoles.len() {
+            return false;
+        }
+
+        self.active = index;
+
+        true
+    }
+
+    /// Returns the maximum number of scrollback lines of each console.
+    pub fn scrollback_limit(&self) -> usize {
+        self.scrollback_limit
+    }
+
+    /// Set the maximum number of scrollback lines of all consoles,
+    /// dropping the oldest lines if there are more.
+    pub fn set_scrollback_limit(&mut self, limit: usize) {
+        self.scrollback_limit = limit.max(1);
+
+        for console in &mut self.consoles {
+            console.set_scrollback_limit(self.scrollback_limit);
+        }
+    }
+
+    /// Returns the console receiving the written text and new apps.
+    ///
+    /// This is the console the running command was entered in or the [Self::SYSTEM_CONSOLE].
+    pub fn console(&mut self) -> &mut Console {
+        &mut self.consoles[self.output]
+    }
+
+    /// Set the [App] of the [console](Self::console).
     ///
     /// If there already was another app active, it will be exited.
-    pub fn set_app(&mut self, app: impl App) {
-        self.app.replace(Box::new(app)).map(|mut app| app.exit());
+    pub fn set_app(&mut self, app: Box<dyn App>) {
+        self.console().set_app(app);
     }
 
-    fn handle_input(&mut self, queue: &SegQueue<String>) {
+    fn handle_input(&mut self, control: &Control) {
         let input = INPUT.get();
-        while let Some(key) = input.pop() {
-            let mut command = AppCommand::Continue;
-
-            if let Some(app) = &mut self.app {
-                command = app.handle_input(key);
-            } else {
-                match key {
-                    DecodedKey::Unicode(ch) => match ch {
-                        // New line => execute
-                        '\n' => {
-                            let command: String = self.command.drain(..).collect();
-
-                            self.string_buf
-                                .push_str(&format!("{} {command}\n", Self::COMMAND_PREFIX));
-
-                            queue.push(command);
-                        }
-
-                        // Backspace => delete last character
-                        '\x08' => {
-                            self.command.pop();
-                        }
-
-                        // Else => push to command
-                        _ => self.command.push(ch),
-                    },
 
-                    DecodedKey::RawKey(code) => match code {
-                        // Scroll up => increment scroll offset
-                        KeyCode::ArrowUp => {
-                            self.scroll_offset = self.scroll_offset.saturating_add(1)
-                        }
+        if let Some(index) = input.take_console() {
+            self.switch(index);
+        }
+
+        let echo = input.has_serial();
 
-                        // Scroll down => decrement scroll offset
-                        KeyCode::ArrowDown => {
-                            self.scroll_offset = self.scroll_offset.saturating_sub(1)
-                        }
+        while let Some(key) = input.pop() {
+            let console = &mut self.consoles[self.active];
+            let previous = echo.then(|| console.command_line()).flatten();
 
-                        // Else => do nothing
-                        _ => (),
-                    },
-                }
-            }
+            console.handle_key(key, control, self.active);
 
-            self.handle_command(command);
+            if echo {
+                console.echo(key, previous);
+            }
         }
     }
 
     fn render(&mut self) {
+        // Parse the temp buffers of all consoles, so they don't grow while hidden or behind an app
+        for console in &mut self.consoles {
+            console.flush(self.max_width);
+        }
+
         let mut command = AppCommand::Continue;
 
-        if let Some(app) = &mut self.app {
+        if let Some(app) = self.consoles[self.active].app_mut() {
             self.terminal
                 .draw(|frame| {
                     command = app.render(frame);
@@ -293,38 +761,10 @@ impl InnerControl {
             self.render_terminal();
         }
 
-        self.handle_command(command);
+        self.consoles[self.active].handle_command(command);
     }
 
     fn render_terminal(&mut self) {
-        // Parse temp buffer if not empty
-        if !self.string_buf.is_empty() {
-            let string = self.string_buf.drain(..).collect::<String>();
-
-            let spans = Span::decode_capacity(&string, Self::PARSE_CAPACITY)
-                .expect("Failed to parse spans");
-
-            let mut current = Vec::with_capacity(self.max_width);
-
-            for span in spans {
-                for ch in span.text.chars() {
-                    if ch == '\n' {
-                        self.lines.push(core::mem::take(&mut current));
-                    } else {
-                        current.push((ch, span.style));
-
-                        if current.len() == self.max_width {
-                            self.lines.push(core::mem::take(&mut current));
-                        }
-                    }
-                }
-            }
-
-            if !current.is_empty() {
-                self.lines.push(current);
-            }
-        }
-
         let screen = Rect::from(
             self.terminal
                 .backend()
@@ -332,21 +772,24 @@ impl InnerControl {
                 .expect("Failed to get backend size"),
         );
 
+        let console = &self.consoles[self.active];
+        let title = format!(" Console {} ", self.active + 1);
+
         self.terminal
             .draw(|frame| {
                 let block = Block::new()
                     .borders(Borders::all())
-                    .border_type(BorderType::Rounded);
+                    .border_type(BorderType::Rounded)
+                    .title(title.as_str());
 
                 let inner = block.inner(screen);
 
                 frame.render_widget(
-                    TerminalBox::new(
-                        &self.lines,
-                        &self.command,
-                        Style::new(Self::FOREGROUND, Self::BACKGROUND, Attributes::empty()),
-                        self.scroll_offset,
-                    ),
+                    console.terminal_box(Style::new(
+                        Self::FOREGROUND,
+                        Self::BACKGROUND,
+                        Attributes::empty(),
+                    )),
                     inner,
                 );
 
@@ -354,42 +797,10 @@ impl InnerControl {
             })
             .expect("Failed to draw terminal");
     }
-
-    fn handle_command(&mut self, command: AppCommand) {
-        match command {
-            AppCommand::SetApp(app) => {
-                self.app.replace(app).unwrap().exit();
-            }
-
-            AppCommand::Exit(msg) => {
-                self.app.take().unwrap().exit();
-
-                if let Some(msg) = msg {
-                    self.string_buf.push_str(&msg);
-                }
-            }
-
-            AppCommand::Multiple(commands) => {
-                for command in commands {
-                    self.handle_command(command);
-                }
-            }
-
-            AppCommand::Continue => (),
-        }
-    }
 }
 
 impl Write for InnerControl {
     fn write_str(&mut self, s: &str) -> core::fmt::Result {
-        for ch in s.chars() {
-            if ch == '\t' {
-                self.string_buf.push_str(Self::EXPANDED_TAB);
-            } else {
-                self.string_buf.push(ch);
-            }
-        }
-
-        Ok(())
+        self.console().write_str(s)
     }
 }
@@ -0,0 +1,108 @@
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// A command line of commands connected by pipes (`|`),
+/// whose output is optionally redirected into a [buffer](crate::control::buffer).
+///
+/// The output of each command is the input of the next one.
+/// Redirection with `> name` replaces the buffer and `>> name` appends to it.
+/// Pipes and redirections inside single or double quotes are ignored, e.g. in `alias a='b | c'`.
+#[derive(Debug)]
+pub struct Pipeline<'a> {
+    /// The commands with their arguments in execution order.
+    pub commands: Vec<&'a str>,
+    /// Where the output of the last command is redirected to.
+    pub redirect: Option<Redirect<'a>>,
+}
+
+/// The redirection of a [Pipeline] into a buffer.
+#[derive(Debug)]
+pub struct Redirect<'a> {
+    /// The name of the buffer.
+    pub buffer: &'a str,
+    /// If the output is appended to the buffer instead of replacing it.
+    pub append: bool,
+}
+
+impl<'a> Pipeline<'a> {
+    /// Parse the given command line.
+    pub fn parse(line: &'a str) -> Result<Self, String> {
+        let redirect = unquoted(line)
+            .find(|(_, ch)| *ch == '>')
+            .map(|(idx, _)| (&line[..idx], &line[idx + 1..]));
+
+        let (piped, redirect) = match redirect {
+            Some((piped, target)) => {
+                let (target, append) = match target.strip_prefix('>') {
+                    Some(target) => (target.trim(), true),
+                    None => (target.trim(), false),
+                };
+
+                if target.is_empty() || target.contains(char::is_whitespace) {
+                    return Err("Expected a single buffer name after '>'.".to_string());
+                }
+
+                if target.contains(['>', '|']) {
+                    return Err("Only the end of a pipeline can be redirected.".to_string());
+                }
+
+                let redirect = Redirect {
+                    buffer: target,
+                    append,
+                };
+
+                (piped, Some(redirect))
+            }
+            None => (line, None),
+        };
+
+        let mut start = 0;
+        let mut commands = Vec::new();
+
+        for (idx, _) in unquoted(piped).filter(|(_, ch)| *ch == '|') {
+            commands.push(piped[start..idx].trim());
+            start = idx + 1;
+        }
+
+        commands.push(piped[start..].trim());
+
+        if commands.len() > 1 && commands.iter().any(|command| command.is_empty()) {
+            return Err("Empty command in pipeline.".to_string());
+        }
+
+        Ok(Self { commands, redirect })
+    }
+}
+
+/// Split a trailing `&` off the given command line, which runs it as background job.
+///
+/// Returns the command line without the `&` and if it was found.
+/// A trailing `&&` or an `&` inside quotes is kept.
+pub fn background(line: &str) -> (&str, bool) {
+    let line = line.trim_end();
+
+    match unquoted(line).last() {
+        Some((idx, '&')) if idx + 1 == line.len() && !line[..idx].ends_with('&') => {
+            (line[..idx].trim_end(), true)
+        }
+        _ => (line, false),
+    }
+}
+
+/// Returns the chars of the given line with their byte offsets, which are not inside quotes.
+fn unquoted(line: &str) -> impl Iterator<Item = (usize, char)> {
+    let mut quote = None;
+
+    line.char_indices().filter(move |(_, ch)| match quote {
+        Some(open) if open == *ch => {
+            quote = None;
+            false
+        }
+        Some(_) => false,
+        None if *ch == '\'' || *ch == '"' => {
+            quote = Some(*ch);
+            false
+        }
+        None => true,
+    })
+}
@@ -0,0 +1,642 @@
+use crate::config::{self, Autorun, BOOT_CONFIG};
+use crate::control::command::CommandIo;
+use crate::control::job::CancelToken;
+use crate::control::{CONTROL, Control, log_error};
+use crate::requests;
+use alloc::collections::BTreeMap;
+use alloc::format;
+use alloc::rc::Rc;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+use core::sync::atomic::{AtomicUsize, Ordering};
+use limine::file::File;
+
+/// The limine module string of the script, which is run once the kernel is set up.
+pub const AUTORUN: &str = "autorun";
+
+/// The limine module string of the profile, which is run before the [AUTORUN] script.
+pub const PROFILE: &str = "profile";
+
+/// The nesting depth of function calls, scripts and command substitutions of all interpreters,
+/// since `source` and `$(...)` run other interpreters.
+static DEPTH: AtomicUsize = AtomicUsize::new(0);
+
+/// The separator of the kernel command line, after which the profile statements follow.
+pub const PROFILE_SEPARATOR: &str = "--";
+
+/// Run the startup profile, which sets up aliases, the time zone, the log level,
+/// the keyboard layout or anything else before the [AUTORUN] script.
+///
+/// The profile is the limine module with the module string [PROFILE], followed by the
+/// statements after [PROFILE_SEPARATOR] on the kernel command line separated by `;`,
+/// e.g. `-- alias ll='module list'; time set CET; keyboard us`.
+pub fn profile() {
+    if let Some((path, source)) = module_script(PROFILE) {
+        run_boot_script(&format!("profile {path}"), &source);
+    }
+
+    if let (_, Some(statements)) = config::split_cmdline(requests::cmdline()) {
+        run_boot_script("command line profile", &statements.replace(';', "\n"));
+    }
+}
+
+/// Run the limine module with the module string [AUTORUN] as script, if there is one.
+///
+/// The `autorun` option of the [BootConfig](config::BootConfig) can disable the script
+/// or select another limine module by its path.
+/// The output of the script is printed to the control, errors are logged.
+pub fn autorun() {
+    let script = match &BOOT_CONFIG.get().autorun {
+        Autorun::Default => module_script(AUTORUN),
+        Autorun::Off => {
+            log::info!("Skipping autorun script, since it is disabled.");
+            return;
+        }
+        Autorun::Module(path) => {
+            let script = requests::module_file(path).and_then(read_script);
+
+            if script.is_none() {
+                log::error!("Autorun script {path} not found.");
+            }
+
+            script
+        }
+    };
+
+    if let Some((path, source)) = script {
+        run_boot_script(&format!("autorun script {path}"), &source);
+    }
+}
+
+/// Returns the path and the content of the limine module with the given module string.
+fn module_script(string: &str) -> Option<(String, String)> {
+    let file = requests::modules()?
+        .modules()
+        .iter()
+        .copied()
+        .find(|file| file.string().to_bytes() == string.as_bytes())?;
+
+    read_script(file)
+}
+
+/// Returns the path and the content of the given limine module.
+fn read_script(file: &File) -> Option<(String, String)> {
+    let path = file.path().to_string_lossy().into_owned();
+
+    let bytes =
+        unsafe { core::slice::from_raw_parts(file.addr() as *const u8, file.size() as usize) };
+
+    let Ok(source) = core::str::from_utf8(bytes) else {
+        log::error!("Script {path} is not valid UTF-8.");
+        return None;
+    };
+
+    Some((path, source.to_string()))
+}
+
+/// Run the given script during boot, print its output to the control and log errors.
+fn run_boot_script(name: &str, source: &str) {
+    log::info!("Running {name}...");
+
+    let control = CONTROL.get();
+    let mut io = CommandIo::new(String::new(), CancelToken::new());
+
+    let result = Script::parse(source)
+        .and_then(|script| Interpreter::new(control, &mut io, Vec::new()).run(&script));
+
+    control
+        .print(io.output())
+        .unwrap_or_else(|err| log::error!("{err}"));
+
+    if let Err(err) = result {
+        log::error!("Running {name} failed: {err}");
+    }
+}
+
+/// Error type returned when parsing or running a [Script] fails.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScriptError {
+    /// The given line is malformed.
+    Syntax(usize, &'static str),
+    /// A loop at the given line exceeded [Interpreter::MAX_ITERATIONS].
+    LoopLimit(usize),
+    /// A function call, script or command substitution at the given line exceeded
+    /// [Interpreter::MAX_DEPTH].
+    RecursionLimit(usize),
+    /// The script was cancelled before the given line.
+    Cancelled(usize),
+}
+
+impl Display for ScriptError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ScriptError::Syntax(line, message) => write!(f, "Line {line}: {message}"),
+            ScriptError::LoopLimit(line) => write!(f, "Line {line}: Too many loop iterations"),
+            ScriptError::RecursionLimit(line) => write!(f, "Line {line}: Too deep recursion"),
+            ScriptError::Cancelled(line) => write!(f, "Line {line}: Cancelled"),
+        }
+    }
+}
+
+/// A parsed control script.
+///
+/// Scripts consist of one statement per line, lines starting with `#` are comments:
+///
+/// - `set <name> = <value>` assigns a variable, which is expanded by `$name` or `${name}`.
+/// - `if <condition>`, `else` and `end` run a block if the condition succeeds or fails.
+/// - `while <condition>` and `end` run a block as long as the condition succeeds.
+/// - `for <name> in <items>` and `end` run a block for each whitespace separated item.
+/// - `fn <name>` and `end` define a function, which is called like a command.
+///   Its arguments are expanded by `$1`, `$2`, ..., `$@` and their count by `$#`.
+/// - `break` leaves the innermost loop and `return` the function or script.
+/// - Every other line is a command line, run by [Control::capture].
+///   Command lines and conditions can be chained with `&&` and `||`.
+///
+/// `$(command)` expands to the output of the command and `$?` to the status of the last
+/// command, `0` on success and `1` on failure.
+pub struct Script {
+    statements: Vec<Statement>,
+}
+
+impl Script {
+    /// Parse the given script source.
+    pub fn parse(source: &str) -> Result<Self, ScriptError> {
+        let mut lines = source
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let (statements, terminator) = parse_block(&mut lines, 0)?;
+
+        match terminator {
+            Some((line, _)) => Err(ScriptError::Syntax(line, "Unexpected 'else' or 'end'")),
+            None => Ok(Self { statements }),
+        }
+    }
+}
+
+/// A statement with the line it starts at.
+struct Statement {
+    line: usize,
+    kind: StatementKind,
+}
+
+enum StatementKind {
+    Command(String),
+    Set(String, String),
+    If(String, Vec<Statement>, Vec<Statement>),
+    While(String, Vec<Statement>),
+    For(String, String, Vec<Statement>),
+    Function(String, Rc<Vec<Statement>>),
+    Break,
+    Return,
+}
+
+/// Parse statements until the end of the source or an `else` or `end` line,
+/// which is returned with its line number.
+fn parse_block<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    start: usize,
+) -> Result<(Vec<Statement>, Option<(usize, &'a str)>), ScriptError> {
+    let mut statements = Vec::new();
+
+    while let Some((line, text)) = lines.next() {
+        let (keyword, rest) = text.split_once(char::is_whitespace).unwrap_or((text, ""));
+        let rest = rest.trim();
+
+        let kind = match keyword {
+            "else" | "end" if rest.is_empty() => return Ok((statements, Some((line, keyword)))),
+
+            "set" => {
+                let (name, value) = rest
+                    .split_once('=')
+                    .ok_or(ScriptError::Syntax(line, "Expected 'set <name> = <value>'"))?;
+
+                StatementKind::Set(parse_name(name.trim(), line)?, value.trim().to_string())
+            }
+
+            "if" => {
+                let (then, terminator) = parse_end(lines, line, &["else", "end"])?;
+
+                let otherwise = if terminator == "else" {
+                    parse_end(lines, line, &["end"])?.0
+                } else {
+                    Vec::new()
+                };
+
+                StatementKind::If(parse_condition(rest, line)?, then, otherwise)
+            }
+
+            "while" => {
+                let (body, _) = parse_end(lines, line, &["end"])?;
+
+                StatementKind::While(parse_condition(rest, line)?, body)
+            }
+
+            "for" => {
+                let (name, items) = rest.split_once(" in ").ok_or(ScriptError::Syntax(
+                    line,
+                    "Expected 'for <name> in <items>'",
+                ))?;
+
+                let (body, _) = parse_end(lines, line, &["end"])?;
+
+                StatementKind::For(
+                    parse_name(name.trim(), line)?,
+                    items.trim().to_string(),
+                    body,
+                )
+            }
+
+            "fn" => {
+                let (body, _) = parse_end(lines, line, &["end"])?;
+
+                StatementKind::Function(parse_name(rest, line)?, Rc::new(body))
+            }
+
+            "break" if rest.is_empty() => StatementKind::Break,
+            "return" if rest.is_empty() => StatementKind::Return,
+
+            _ => StatementKind::Command(text.to_string()),
+        };
+
+        statements.push(Statement { line, kind });
+    }
+
+    if start > 0 {
+        return Err(ScriptError::Syntax(start, "Missing 'end'"));
+    }
+
+    Ok((statements, None))
+}
+
+/// Parse the block of the statement at line `start`, which must end with one of `terminators`.
+fn parse_end<'a>(
+    lines: &mut impl Iterator<Item = (usize, &'a str)>,
+    start: usize,
+    terminators: &[&str],
+) -> Result<(Vec<Statement>, &'a str), ScriptError> {
+    match parse_block(lines, start)? {
+        (statements, Some((_, terminator))) if terminators.contains(&terminator) => {
+            Ok((statements, terminator))
+        }
+        (_, Some((line, _))) => Err(ScriptError::Syntax(line, "Unexpected 'else'")),
+        (_, None) => Err(ScriptError::Syntax(start, "Missing 'end'")),
+    }
+}
+
+fn parse_name(name: &str, line: usize) -> Result<String, ScriptError> {
+    let valid = name.starts_with(|ch: char| ch.is_ascii_alphabetic() || ch == '_')
+        && name
+            .chars()
+            .all(|ch| ch.is_ascii_alphanumeric() || ch == '_');
+
+    if !valid {
+        return Err(ScriptError::Syntax(line, "Invalid name"));
+    }
+
+    Ok(name.to_string())
+}
+
+fn parse_condition(condition: &str, line: usize) -> Result<String, ScriptError> {
+    if condition.is_empty() {
+        return Err(ScriptError::Syntax(line, "Missing condition"));
+    }
+
+    Ok(condition.to_string())
+}
+
+/// What to do after a statement was run.
+enum Flow {
+    Next,
+    Break,
+    Return,
+}
+
+/// Runs [Script]s on the [Control].
+///
+/// The output of all command lines is written to the given [CommandIo],
+/// errors of commands are logged and only change the status.
+/// Each statement is a [checkpoint](CommandIo::checkpoint), where the script can be cancelled.
+pub struct Interpreter<'a> {
+    control: &'a Control,
+    io: &'a mut CommandIo,
+    variables: BTreeMap<String, String>,
+    functions: BTreeMap<String, Rc<Vec<Statement>>>,
+    arguments: Vec<String>,
+    status: bool,
+}
+
+impl<'a> Interpreter<'a> {
+    /// The maximum number of iterations of a single loop.
+    pub const MAX_ITERATIONS: usize = 10_000;
+    /// The maximum depth of nested function calls, scripts and command substitutions,
+    /// shared by all interpreters.
+    pub const MAX_DEPTH: usize = 32;
+
+    /// Create a new interpreter with the given script arguments.
+    pub fn new(control: &'a Control, io: &'a mut CommandIo, arguments: Vec<String>) -> Self {
+        Self {
+            control,
+            io,
+            variables: BTreeMap::new(),
+            functions: BTreeMap::new(),
+            arguments,
+            status: true,
+        }
+    }
+
+    /// Run the given script and return the status of the last command.
+    pub fn run(&mut self, script: &Script) -> Result<bool, ScriptError> {
+        let line = script
+            .statements
+            .first()
+            .map_or(1, |statement| statement.line);
+
+        Self::nested(line, || self.run_block(&script.statements))??;
+
+        Ok(self.status)
+    }
+
+    fn run_block(&mut self, statements: &[Statement]) -> Result<Flow, ScriptError> {
+        for statement in statements {
+            self.io
+                .checkpoint()
+                .map_err(|_| ScriptError::Cancelled(statement.line))?;
+
+            match self.run_statement(statement)? {
+                Flow::Next => (),
+                flow => return Ok(flow),
+            }
+        }
+
+        Ok(Flow::Next)
+    }
+
+    fn run_statement(&mut self, statement: &Statement) -> Result<Flow, ScriptError> {
+        let line = statement.line;
+
+        match &statement.kind {
+            StatementKind::Command(text) => {
+                self.run_condition(text, line)?;
+            }
+
+            StatementKind::Set(name, value) => {
+                let value = self.expand(value, line)?;
+
+                self.variables.insert(name.clone(), value);
+            }
+
+            StatementKind::If(condition, then, otherwise) => {
+                return if self.run_condition(condition, line)? {
+                    self.run_block(then)
+                } else {
+                    self.run_block(otherwise)
+                };
+            }
+
+            StatementKind::While(condition, body) => {
+                let mut iterations = 0;
+
+                while self.run_condition(condition, line)? {
+                    iterations += 1;
+
+                    if iterations > Self::MAX_ITERATIONS {
+                        return Err(ScriptError::LoopLimit(line));
+                    }
+
+                    match self.run_block(body)? {
+                        Flow::Next => (),
+                        Flow::Break => break,
+                        Flow::Return => return Ok(Flow::Return),
+                    }
+                }
+            }
+
+            StatementKind::For(name, items, body) => {
+                let items = self.expand(items, line)?;
+
+                for item in items.split_whitespace() {
+                    self.variables.insert(name.clone(), item.to_string());
+
+                    match self.run_block(body)? {
+                        Flow::Next => (),
+                        Flow::Break => break,
+                        Flow::Return => return Ok(Flow::Return),
+                    }
+                }
+            }
+
+            StatementKind::Function(name, body) => {
+                self.functions.insert(name.clone(), body.clone());
+            }
+
+            StatementKind::Break => return Ok(Flow::Break),
+            StatementKind::Return => return Ok(Flow::Return),
+        }
+
+        Ok(Flow::Next)
+    }
+
+    /// Run command lines chained with `&&` and `||` and return the resulting status.
+    fn run_condition(&mut self, condition: &str, line: usize) -> Result<bool, ScriptError> {
+        for (operator, command) in split_chain(condition) {
+            let skip = match operator {
+                Some(Operator::And) => !self.status,
+                Some(Operator::Or) => self.status,
+                None => false,
+            };
+
+            if !skip {
+                self.status = self.run_command(command, line)?;
+            }
+        }
+
+        Ok(self.status)
+    }
+
+    /// Run a single command line or function call and return if it succeeded.
+    fn run_command(&mut self, command: &str, line: usize) -> Result<bool, ScriptError> {
+        let command = self.expand(command, line)?;
+
+        let mut words = command.split_whitespace();
+
+        if let Some(body) = words
+            .next()
+            .and_then(|name| self.functions.get(name).cloned())
+        {
+            let arguments = words.map(ToString::to_string).collect();
+            let arguments = core::mem::replace(&mut self.arguments, arguments);
+
+            let result = Self::nested(line, || self.run_block(&body)).and_then(|result| result);
+
+            self.arguments = arguments;
+
+            return result.map(|_| self.status);
+        }
+
+        let Some(output) = self.capture(&command) else {
+            return Ok(false);
+        };
+
+        write!(self.io, "{output}");
+
+        Ok(true)
+    }
+
+    /// Run the given function one level deeper in the nesting of all interpreters.
+    ///
+    /// Fails at the given line if the nesting exceeds [Self::MAX_DEPTH],
+    /// so recursive scripts can't overflow the kernel stack.
+    fn nested<R>(line: usize, func: impl FnOnce() -> R) -> Result<R, ScriptError> {
+        if DEPTH.fetch_add(1, Ordering::Relaxed) >= Self::MAX_DEPTH {
+            DEPTH.fetch_sub(1, Ordering::Relaxed);
+
+            return Err(ScriptError::RecursionLimit(line));
+        }
+
+        let result = func();
+
+        DEPTH.fetch_sub(1, Ordering::Relaxed);
+
+        Ok(result)
+    }
+
+    /// Run the command line and return its output or [None] if the command failed.
+    fn capture(&mut self, command: &str) -> Option<String> {
+        match self.control.capture(command) {
+            Ok(output) => Some(output),
+            Err(err) => {
+                log_error(&err);
+                None
+            }
+        }
+    }
+
+    /// Expand the variables and command substitutions in the given text.
+    fn expand(&mut self, text: &str, line: usize) -> Result<String, ScriptError> {
+        let mut expanded = String::with_capacity(text.len());
+        let mut rest = text;
+
+        while let Some(index) = rest.find('$') {
+            expanded.push_str(&rest[..index]);
+            rest = &rest[index + 1..];
+
+            if let Some(inner) = rest.strip_prefix('(') {
+                let end = closing_paren(inner)
+                    .ok_or(ScriptError::Syntax(line, "Unclosed command substitution"))?;
+
+                let command = self.expand(&inner[..end], line)?;
+                let output = Self::nested(line, || self.capture(&command))?;
+
+                self.status = output.is_some();
+                expanded.push_str(output.unwrap_or_default().trim_end_matches('\n'));
+
+                rest = &inner[end + 1..];
+            } else if let Some(inner) = rest.strip_prefix('{') {
+                let end = inner
+                    .find('}')
+                    .ok_or(ScriptError::Syntax(line, "Unclosed variable"))?;
+
+                expanded.push_str(&self.variable(&inner[..end]));
+
+                rest = &inner[end + 1..];
+            } else {
+                let len = if rest.starts_with(['?', '#', '@']) {
+                    1
+                } else {
+                    rest.find(|ch: char| !ch.is_ascii_alphanumeric() && ch != '_')
+                        .unwrap_or(rest.len())
+                };
+
+                if len == 0 {
+                    expanded.push('$');
+                } else {
+                    expanded.push_str(&self.variable(&rest[..len]));
+                }
+
+                rest = &rest[len..];
+            }
+        }
+
+        expanded.push_str(rest);
+
+        Ok(expanded)
+    }
+
+    /// Returns the value of the variable with the given name, which is empty if it is not set.
+    fn variable(&self, name: &str) -> String {
+        match name {
+            "?" => (if self.status { "0" } else { "1" }).to_string(),
+            "#" => self.arguments.len().to_string(),
+            "@" => self.arguments.join(" "),
+            _ => match name.parse::<usize>() {
+                Ok(0) => String::new(),
+                Ok(index) => self.arguments.get(index - 1).cloned().unwrap_or_default(),
+                Err(_) => self.variables.get(name).cloned().unwrap_or_default(),
+            },
+        }
+    }
+}
+
+/// The operator chaining two command lines.
+#[derive(Copy, Clone)]
+enum Operator {
+    And,
+    Or,
+}
+
+/// Split command lines chained with `&&` and `||`, ignoring operators inside of `$(...)`.
+fn split_chain(text: &str) -> Vec<(Option<Operator>, &str)> {
+    let bytes = text.as_bytes();
+    let mut chain = Vec::new();
+    let mut operator = None;
+    let mut depth = 0usize;
+    let mut start = 0;
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'(' => depth += 1,
+            b')' => depth = depth.saturating_sub(1),
+            b'&' | b'|' if depth == 0 && bytes.get(i + 1) == Some(&bytes[i]) => {
+                chain.push((operator, text[start..i].trim()));
+
+                operator = Some(if bytes[i] == b'&' {
+                    Operator::And
+                } else {
+                    Operator::Or
+                });
+
+                i += 2;
+                start = i;
+                continue;
+            }
+            _ => (),
+        }
+
+        i += 1;
+    }
+
+    chain.push((operator, text[start..].trim()));
+
+    chain
+}
+
+/// Returns the index of the parenthesis closing the already opened one.
+fn closing_paren(text: &str) -> Option<usize> {
+    let mut depth = 0usize;
+
+    for (index, ch) in text.char_indices() {
+        match ch {
+            '(' => depth += 1,
+            ')' if depth == 0 => return Some(index),
+            ')' => depth -= 1,
+            _ => (),
+        }
+    }
+
+    None
+}
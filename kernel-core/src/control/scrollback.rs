@@ -0,0 +1,103 @@
+use alloc::collections::VecDeque;
+use alloc::vec::Vec;
+use ustyle::Style;
+
+/// A rendered line of the scrollback.
+pub type Line = Vec<(char, Style)>;
+
+/// The lines of a [Console](super::console::Console) in a ring buffer with a maximum length.
+///
+/// Once the limit is reached, the oldest line is dropped for each new line.
+pub struct Scrollback {
+    lines: VecDeque<Line>,
+    limit: usize,
+}
+
+impl Scrollback {
+    /// The default maximum number of lines.
+    pub const DEFAULT_LIMIT: usize = 1000;
+
+    /// Create a new, empty scrollback holding at most `limit` lines.
+    pub fn new(limit: usize) -> Self {
+        Self {
+            lines: VecDeque::with_capacity(limit.min(Self::DEFAULT_LIMIT)),
+            limit: limit.max(1),
+        }
+    }
+
+    /// Append a line and return if the oldest line was dropped for it.
+    pub fn push(&mut self, line: Line) -> bool {
+        let dropped = self.lines.len() == self.limit;
+
+        if dropped {
+            self.lines.pop_front();
+        }
+
+        self.lines.push_back(line);
+
+        dropped
+    }
+
+    /// Returns the line with the given index, counted from the oldest line.
+    pub fn get(&self, index: usize) -> Option<&Line> {
+        self.lines.get(index)
+    }
+
+    /// Returns the number of lines.
+    pub fn len(&self) -> usize {
+        self.lines.len()
+    }
+
+    /// Returns `true` if there are no lines.
+    pub fn is_empty(&self) -> bool {
+        self.lines.is_empty()
+    }
+
+    /// Remove all lines.
+    pub fn clear(&mut self) {
+        self.lines.clear();
+    }
+
+    /// Returns the maximum number of lines.
+    pub fn limit(&self) -> usize {
+        self.limit
+    }
+
+    /// Set the maximum number of lines, dropping the oldest lines if there are more.
+    ///
+    /// Returns the number of dropped lines.
+    pub fn set_limit(&mut self, limit: usize) -> usize {
+        self.limit = limit.max(1);
+
+        let dropped = self.lines.len().saturating_sub(self.limit);
+
+        self.lines.drain(..dropped);
+
+        dropped
+    }
+
+    /// Find the next line containing the pattern, starting at the line with the given index.
+    ///
+    /// Searches towards older lines if `backwards` is `true` and towards newer lines otherwise.
+    pub fn find(&self, pattern: &[char], start: usize, backwards: bool) -> Option<usize> {
+        let contains = |index: &usize| matches(&self.lines[*index], pattern).next().is_some();
+
+        if backwards {
+            (0..=start.min(self.lines.len().checked_sub(1)?))
+                .rev()
+                .find(contains)
+        } else {
+            (start..self.lines.len()).find(contains)
+        }
+    }
+}
+
+/// Returns the char positions at which the pattern starts in the given line.
+pub fn matches<'a>(line: &'a [(char, Style)], pattern: &'a [char]) -> impl Iterator<Item = usize> {
+    line.windows(pattern.len().max(1))
+        .enumerate()
+        .filter(move |(_, window)| {
+            !pattern.is_empty() && window.iter().map(|(ch, _)| ch).eq(pattern.iter())
+        })
+        .map(|(index, _)| index)
+}
@@ -0,0 +1,167 @@
+use crate::control::input::InputControl;
+use crate::serial_print;
+use pc_keyboard::{DecodedKey, KeyCode};
+
+/// Print text of the control to the serial port.
+///
+/// New lines are written as `\r\n`, so the text is displayed correctly by raw terminals.
+pub fn print(text: &str) {
+    for (idx, line) in text.split('\n').enumerate() {
+        if idx > 0 {
+            serial_print!("\r\n");
+        }
+
+        serial_print!("{line}");
+    }
+}
+
+/// The maximum number of parameter bytes of a control sequence.
+const MAX_PARAMS: usize = 8;
+
+/// Decodes the bytes received from a terminal on the serial port into keys of the [InputControl].
+///
+/// Handles UTF-8 and the common VT100/xterm escape sequences for the arrow keys, Home, End,
+/// Delete, Page Up and Page Down, F1 to F4 and Alt+F1 to Alt+F4 for switching consoles.
+/// The keys are only queued, the control echoes the edited command line from its update,
+/// since the terminal does not echo in raw mode.
+pub struct SerialDecoder {
+    state: State,
+    /// If the previous byte was a carriage return, so a following line feed is skipped.
+    carriage_return: bool,
+}
+
+/// The state of the [SerialDecoder].
+enum State {
+    Ground,
+    /// After an escape byte.
+    Escape,
+    /// Inside a control sequence (`ESC [`) with the given parameter bytes.
+    Csi([u8; MAX_PARAMS], usize),
+    /// After `ESC O`.
+    Ss3,
+    /// Inside a UTF-8 sequence with the given bytes and the total length.
+    Utf8([u8; 4], usize, usize),
+}
+
+impl SerialDecoder {
+    /// Create a new serial decoder.
+    pub const fn new() -> Self {
+        Self {
+            state: State::Ground,
+            carriage_return: false,
+        }
+    }
+
+    /// Decode the given received byte and push the completed keys to the input.
+    ///
+    /// This does not allocate, so it's safe to call in interrupt context.
+    pub fn push(&mut self, byte: u8, input: &InputControl) {
+        let carriage_return = core::mem::replace(&mut self.carriage_return, byte == b'\r');
+
+        match core::mem::replace(&mut self.state, State::Ground) {
+            State::Ground => match byte {
+                0x1b => self.state = State::Escape,
+                b'\n' if carriage_return => (),
+                b'\r' | b'\n' => Self::key(DecodedKey::Unicode('\n'), input),
+                0x7f | 0x08 => Self::key(DecodedKey::Unicode('\x08'), input),
+                0x00..=0x7f => Self::key(DecodedKey::Unicode(byte as char), input),
+                0xc0..=0xdf => self.state = State::Utf8([byte, 0, 0, 0], 1, 2),
+                0xe0..=0xef => self.state = State::Utf8([byte, 0, 0, 0], 1, 3),
+                0xf0..=0xf7 => self.state = State::Utf8([byte, 0, 0, 0], 1, 4),
+                _ => (),
+            },
+
+            State::Escape => match byte {
+                b'[' => self.state = State::Csi([0; MAX_PARAMS], 0),
+                b'O' => self.state = State::Ss3,
+                _ => {
+                    // A single Esc key followed by another key
+                    Self::key(DecodedKey::Unicode('\u{1b}'), input);
+                    self.push(byte, input);
+                }
+            },
+
+            State::Csi(mut params, len) => match byte {
+                0x30..=0x3f => {
+                    if len < MAX_PARAMS {
+                        params[len] = byte;
+                    }
+
+                    self.state = State::Csi(params, len + 1);
+                }
+                0x40..=0x7e => Self::csi(&params[..len.min(MAX_PARAMS)], byte, input),
+                // Malformed sequence
+                _ => (),
+            },
+
+            State::Ss3 => {
+                if let Some(code) = Self::final_key(byte) {
+                    Self::key(DecodedKey::RawKey(code), input);
+                }
+            }
+
+            State::Utf8(mut bytes, len, total) => {
+                if byte & 0xc0 != 0x80 {
+                    // Invalid continuation byte, so start over with this byte
+                    self.push(byte, input);
+                    return;
+                }
+
+                bytes[len] = byte;
+
+                if len + 1 < total {
+                    self.state = State::Utf8(bytes, len + 1, total);
+                } else if let Some(ch) = core::str::from_utf8(&bytes[..total])
+                    .ok()
+                    .and_then(|str| str.chars().next())
+                {
+                    Self::key(DecodedKey::Unicode(ch), input);
+                }
+            }
+        }
+    }
+
+    /// Handle the control sequence with the given parameters and final byte.
+    fn csi(params: &[u8], byte: u8, input: &InputControl) {
+        let code = match (params, byte) {
+            // Alt+F1 to Alt+F4 => switch virtual console
+            (b"1;3", b'P'..=b'S') => {
+                input.request_console((byte - b'P') as usize);
+                return;
+            }
+            (b"1" | b"7", b'~') => KeyCode::Home,
+            (b"4" | b"8", b'~') => KeyCode::End,
+            (b"3", b'~') => KeyCode::Delete,
+            (b"5", b'~') => KeyCode::PageUp,
+            (b"6", b'~') => KeyCode::PageDown,
+            (_, byte) => match Self::final_key(byte) {
+                Some(code) => code,
+                None => return,
+            },
+        };
+
+        Self::key(DecodedKey::RawKey(code), input);
+    }
+
+    /// Returns the key of the final byte of an escape sequence without parameters.
+    fn final_key(byte: u8) -> Option<KeyCode> {
+        match byte {
+            b'A' => Some(KeyCode::ArrowUp),
+            b'B' => Some(KeyCode::ArrowDown),
+            b'C' => Some(KeyCode::ArrowRight),
+            b'D' => Some(KeyCode::ArrowLeft),
+            b'H' => Some(KeyCode::Home),
+            b'F' => Some(KeyCode::End),
+            b'P' => Some(KeyCode::F1),
+            b'Q' => Some(KeyCode::F2),
+            b'R' => Some(KeyCode::F3),
+            b'S' => Some(KeyCode::F4),
+            _ => None,
+        }
+    }
+
+    /// Push the key to the input.
+    fn key(key: DecodedKey, input: &InputControl) {
+        input.push_serial(key);
+    }
+}
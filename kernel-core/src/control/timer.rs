@@ -0,0 +1,22 @@
+use crate::control::Control;
+use crate::control::job::CancelToken;
+use alloc::boxed::Box;
+
+/// The function of a [Timer], which returns `false` to stop the timer.
+pub type TimerFn = Box<dyn FnMut(&Control) -> bool + Send>;
+
+/// A function run periodically by the [Control] on its update, outside the control lock,
+/// so it can run commands.
+///
+/// The timer is removed once its token is cancelled or its function returns `false`.
+pub struct Timer {
+    /// The interval in timer ticks.
+    pub(super) interval: u64,
+    /// The tick of the next run.
+    pub(super) next: u64,
+    /// The index of the console receiving the output written by the function.
+    pub(super) console: usize,
+    /// The token stopping the timer, which is also passed to the commands run by the function.
+    pub(super) token: CancelToken,
+    pub(super) func: TimerFn,
+}
@@ -0,0 +1,35 @@
+use core::fmt::{Display, Formatter};
+
+/// Error type returned by IPC operations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum IpcError {
+    /// There is no message to receive yet.
+    WouldBlock,
+    /// The other endpoint of the channel was closed.
+    PeerClosed,
+    /// The message queue of the receiving endpoint is full.
+    QueueFull,
+    /// The message exceeds [MAX_MESSAGE_SIZE](super::MAX_MESSAGE_SIZE)
+    /// or [MAX_MESSAGE_HANDLES](super::MAX_MESSAGE_HANDLES).
+    MessageTooLarge,
+    /// The next message does not fit into the given receive buffers.
+    BufferTooSmall,
+    /// No service is published with the given name.
+    ServiceNotFound,
+    /// A service with the given name is already published.
+    ServiceAlreadyExists,
+}
+
+impl Display for IpcError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            IpcError::WouldBlock => write!(f, "No message available"),
+            IpcError::PeerClosed => write!(f, "Peer endpoint closed"),
+            IpcError::QueueFull => write!(f, "Message queue full"),
+            IpcError::MessageTooLarge => write!(f, "Message too large"),
+            IpcError::BufferTooSmall => write!(f, "Receive buffer too small"),
+            IpcError::ServiceNotFound => write!(f, "Service not found"),
+            IpcError::ServiceAlreadyExists => write!(f, "Service already exists"),
+        }
+    }
+}
@@ -0,0 +1,235 @@
+use crate::ipc::error::IpcError;
+use crate::process;
+use crate::sync::mutex::Mutex;
+use alloc::collections::{BTreeMap, VecDeque};
+use alloc::string::{String, ToString};
+use alloc::sync::Arc;
+use alloc::vec::Vec;
+
+/// Contains the [IpcError] type.
+pub mod error;
+
+/// The maximum number of data bytes in a single [Message].
+///
+/// As of right now, it's equal to 64 KB.
+pub const MAX_MESSAGE_SIZE: usize = 64 * 1024;
+
+/// The maximum number of handles transferred with a single [Message].
+pub const MAX_MESSAGE_HANDLES: usize = 8;
+
+/// The maximum number of messages queued at an endpoint before [IpcError::QueueFull] is returned.
+pub const MAX_QUEUED_MESSAGES: usize = 64;
+
+/// The published services, mapping a name to the endpoint connection requests are sent to.
+static SERVICES: Mutex<BTreeMap<String, Endpoint>> = Mutex::new(BTreeMap::new());
+
+/// A message sent through a channel.
+///
+/// Besides plain bytes, a message can carry [Endpoint]s, which moves them to the receiver.
+pub struct Message {
+    /// The data of the message.
+    pub data: Vec<u8>,
+    /// The endpoints transferred with the message.
+    pub handles: Vec<Endpoint>,
+}
+
+impl Message {
+    /// Create a new message with the given data and handles.
+    pub fn new(data: Vec<u8>, handles: Vec<Endpoint>) -> Self {
+        Self { data, handles }
+    }
+
+    /// Create a new message that only contains data.
+    pub fn bytes(data: impl Into<Vec<u8>>) -> Self {
+        Self::new(data.into(), Vec::new())
+    }
+}
+
+/// The shared state of the two endpoints of a channel.
+struct Channel {
+    /// The messages queued for each side.
+    queues: [VecDeque<Message>; 2],
+    /// If the endpoint of each side was closed.
+    closed: [bool; 2],
+}
+
+/// One end of a bidirectional, kernel managed message channel.
+///
+/// Messages sent through an endpoint are received at its peer, and vice versa.
+/// Dropping an endpoint closes it, after which its peer receives [IpcError::PeerClosed].
+pub struct Endpoint {
+    channel: Arc<Mutex<Channel>>,
+    side: usize,
+}
+
+impl Endpoint {
+    /// Create a new channel and return both of its endpoints.
+    pub fn pair() -> (Endpoint, Endpoint) {
+        let channel = Arc::new(Mutex::new(Channel {
+            queues: [VecDeque::new(), VecDeque::new()],
+            closed: [false; 2],
+        }));
+
+        (
+            Endpoint {
+                channel: channel.clone(),
+                side: 0,
+            },
+            Endpoint { channel, side: 1 },
+        )
+    }
+
+    /// Send a message to the peer endpoint.
+    ///
+    /// The message, including its handles, is dropped if sending fails.
+    pub fn send(&self, message: Message) -> Result<(), IpcError> {
+        if message.data.len() > MAX_MESSAGE_SIZE || message.handles.len() > MAX_MESSAGE_HANDLES {
+            return Err(IpcError::MessageTooLarge);
+        }
+
+        let peer = self.peer();
+        let mut message = Some(message);
+
+        // The message is only moved in on success, since dropping endpoints under the lock
+        // could deadlock if they belong to this channel
+        self.channel.run(|channel| {
+            if channel.closed[peer] {
+                Err(IpcError::PeerClosed)
+            } else if channel.queues[peer].len() >= MAX_QUEUED_MESSAGES {
+                Err(IpcError::QueueFull)
+            } else {
+                channel.queues[peer].extend(message.take());
+                Ok(())
+            }
+        })
+    }
+
+    /// Receive the next message without blocking.
+    ///
+    /// Returns [IpcError::WouldBlock] if there is no message yet.
+    pub fn try_recv(&self) -> Result<Message, IpcError> {
+        self.try_recv_within(MAX_MESSAGE_SIZE, MAX_MESSAGE_HANDLES)
+    }
+
+    /// Receive the next message without blocking, if it fits into the given limits.
+    ///
+    /// Returns [IpcError::BufferTooSmall] and keeps the message queued otherwise.
+    pub fn try_recv_within(
+        &self,
+        max_data: usize,
+        max_handles: usize,
+    ) -> Result<Message, IpcError> {
+        self.channel.run(|channel| {
+            let queue = &mut channel.queues[self.side];
+
+            match queue.front() {
+                Some(message)
+                    if message.data.len() > max_data || message.handles.len() > max_handles =>
+                {
+                    Err(IpcError::BufferTooSmall)
+                }
+                Some(_) => Ok(queue.pop_front().unwrap()),
+                None if channel.closed[self.peer()] => Err(IpcError::PeerClosed),
+                None => Err(IpcError::WouldBlock),
+            }
+        })
+    }
+
+    /// Receive the next message, blocking until one arrives or the peer is closed.
+    ///
+    /// User processes keep running while waiting, so they can answer.
+    /// Must not be called from a system call handler.
+    pub fn recv(&self) -> Result<Message, IpcError> {
+        loop {
+            match self.try_recv() {
+                Err(IpcError::WouldBlock) => process::run_update(),
+                result => return result,
+            }
+        }
+    }
+
+    /// Put a received message back to the front of the queue, e.g. if it could not be delivered.
+    ///
+    /// The message is received again next, even if the queue is full.
+    pub fn requeue(&self, message: Message) {
+        self.channel
+            .run(|channel| channel.queues[self.side].push_front(message));
+    }
+
+    /// Returns if the peer endpoint was closed.
+    pub fn is_peer_closed(&self) -> bool {
+        self.channel.run(|channel| channel.closed[self.peer()])
+    }
+
+    fn peer(&self) -> usize {
+        1 - self.side
+    }
+}
+
+impl Drop for Endpoint {
+    fn drop(&mut self) {
+        let queued = self.channel.run(|channel| {
+            channel.closed[self.side] = true;
+
+            core::mem::take(&mut channel.queues[self.side])
+        });
+
+        // Dropped outside the lock, since queued messages may carry endpoints of this channel
+        drop(queued);
+    }
+}
+
+/// Publish a service with the given name.
+///
+/// Returns the listener endpoint. For every [connect] call, it receives a message
+/// without data, carrying the server side endpoint of the new connection as its only handle.
+/// The service is unpublished once the listener is closed.
+pub fn publish(name: &str) -> Result<Endpoint, IpcError> {
+    SERVICES.run(|services| {
+        if services
+            .get(name)
+            .is_some_and(|endpoint| !endpoint.is_peer_closed())
+        {
+            return Err(IpcError::ServiceAlreadyExists);
+        }
+
+        let (listener, requests) = Endpoint::pair();
+
+        services.insert(name.to_string(), requests);
+
+        Ok(listener)
+    })
+}
+
+/// Connect to the service with the given name.
+///
+/// Returns the client side endpoint of the new connection.
+pub fn connect(name: &str) -> Result<Endpoint, IpcError> {
+    let (client, server) = Endpoint::pair();
+
+    SERVICES.run(|services| {
+        let requests = services.get(name).ok_or(IpcError::ServiceNotFound)?;
+
+        match requests.send(Message::new(Vec::new(), alloc::vec![server])) {
+            Err(IpcError::PeerClosed) => {
+                services.remove(name);
+
+                Err(IpcError::ServiceNotFound)
+            }
+            result => result,
+        }
+    })?;
+
+    Ok(client)
+}
+
+/// Returns the names of all published services.
+pub fn services() -> Vec<String> {
+    SERVICES.run(|services| {
+        services
+            .iter()
+            .filter(|(_, requests)| !requests.is_peer_closed())
+            .map(|(name, _)| name.clone())
+            .collect()
+    })
+}
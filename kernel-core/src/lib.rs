@@ -19,6 +19,9 @@ pub mod logger;
 /// Contains limine request globals.
 pub mod requests;
 
+/// Contains the [config::BootConfig] parsed from the kernel command line.
+pub mod config;
+
 /// Contains serial port infrastructure.
 pub mod serial;
 
@@ -52,3 +55,9 @@ pub mod rand;
 
 /// Contains kernel module infrastructure.
 pub mod module;
+
+/// Contains user process infrastructure.
+pub mod process;
+
+/// Contains message-passing IPC infrastructure.
+pub mod ipc;
@@ -1,6 +1,7 @@
-use crate::control;
+use crate::config::{BOOT_CONFIG, LogConsole};
 use crate::control::CONTROL;
 use crate::serial::SerialWriter;
+use crate::{config, control};
 use core::fmt;
 use core::fmt::{Arguments, Write};
 use log::{Level, LevelFilter, Log, Metadata, Record};
@@ -93,10 +94,19 @@ impl Log for Logger {
         let level = record.level();
         let args = record.args();
 
-        writeln!(&mut SerialWriter, "[{}] {args}", level_to_str(level))
-            .expect("Failed to write log to serial");
+        let console = if config::is_init() {
+            BOOT_CONFIG.get().console
+        } else {
+            LogConsole::Both
+        };
 
-        if control::is_init() {
+        // Logs are written to the serial port until the control is initialized
+        if console != LogConsole::Screen || !control::is_init() {
+            writeln!(&mut SerialWriter, "[{}] {args}", level_to_str(level))
+                .expect("Failed to write log to serial");
+        }
+
+        if console != LogConsole::Serial && control::is_init() {
             CONTROL.get().run(|ctrl| {
                 self.write(
                     level,
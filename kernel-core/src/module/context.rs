@@ -0,0 +1,143 @@
+use crate::control::CONTROL;
+use crate::control::app::{self, AppFactory};
+use crate::control::command::Command;
+use alloc::vec::Vec;
+
+/// Passed to the `init` hook of a module to register extensions with the kernel.
+///
+/// Everything registered through the context is unregistered automatically,
+/// when the module is unloaded or fails to initialize.
+///
+/// The registration functions are function pointers into the kernel,
+/// so the registries of the kernel are used instead of the copies linked into the module.
+pub struct ModuleContext {
+    registrations: Registrations,
+    register_command: fn(&mut Registrations, Command) -> bool,
+    register_app: fn(&mut Registrations, &'static str, AppFactory) -> bool,
+    #[cfg(feature = "pci")]
+    register_pci_driver: fn(
+        &mut Registrations,
+        alloc::boxed::Box<dyn crate::device::pci::PciDriver>,
+    ) -> Result<(), crate::device::pci::error::PciError>,
+}
+
+/// The names of everything a module registered.
+#[derive(Default)]
+struct Registrations {
+    commands: Vec<&'static str>,
+    apps: Vec<&'static str>,
+    #[cfg(feature = "pci")]
+    pci_drivers: Vec<&'static str>,
+}
+
+impl ModuleContext {
+    /// Create a new, empty context.
+    pub(crate) fn new() -> Self {
+        Self {
+            registrations: Registrations::default(),
+            register_command: Registrations::command,
+            register_app: Registrations::app,
+            #[cfg(feature = "pci")]
+            register_pci_driver: Registrations::pci_driver,
+        }
+    }
+
+    /// Register a command for the control.
+    ///
+    /// Returns `false` if a command with the same name is already registered.
+    pub fn register_command(&mut self, command: Command) -> bool {
+        (self.register_command)(&mut self.registrations, command)
+    }
+
+    /// Register an app, which can be launched with the `game` command.
+    ///
+    /// Returns `false` if an app with the same name is already registered.
+    pub fn register_app(&mut self, name: &'static str, factory: AppFactory) -> bool {
+        (self.register_app)(&mut self.registrations, name, factory)
+    }
+
+    /// Register a PCI driver at the global PCI hub.
+    #[cfg(feature = "pci")]
+    pub fn register_pci_driver(
+        &mut self,
+        driver: alloc::boxed::Box<dyn crate::device::pci::PciDriver>,
+    ) -> Result<(), crate::device::pci::error::PciError> {
+        (self.register_pci_driver)(&mut self.registrations, driver)
+    }
+
+    /// Returns the name of a registered command that is executing
+    /// or of a registered app with a live instance.
+    ///
+    /// Both run code of the module, so it must not be unloaded while one exists.
+    pub(crate) fn busy(&self) -> Option<&'static str> {
+        let commands = self.registrations.commands.iter();
+        let apps = self.registrations.apps.iter();
+
+        commands
+            .filter(|name| CONTROL.get().is_running(name))
+            .chain(apps.filter(|name| app::is_live(name)))
+            .next()
+            .copied()
+    }
+
+    /// Unregister everything that was registered through this context.
+    pub(crate) fn unregister_all(&mut self) {
+        let registrations = core::mem::take(&mut self.registrations);
+
+        for name in registrations.commands {
+            CONTROL.get().unregister(name);
+        }
+
+        for name in registrations.apps {
+            app::unregister(name);
+        }
+
+        #[cfg(feature = "pci")]
+        for name in registrations.pci_drivers {
+            use crate::device::DeviceHub;
+            use crate::device::pci::PCI_HUB;
+
+            // Destroys the driver for all of its devices
+            let _ = PCI_HUB.get().run_mut(|hub| hub.unregister(name));
+        }
+    }
+}
+
+impl Registrations {
+    fn command(&mut self, command: Command) -> bool {
+        let registered = CONTROL.get().register(command);
+
+        if registered {
+            self.commands.push(command.name);
+        }
+
+        registered
+    }
+
+    fn app(&mut self, name: &'static str, factory: AppFactory) -> bool {
+        let registered = app::register(name, factory);
+
+        if registered {
+            self.apps.push(name);
+        }
+
+        registered
+    }
+
+    #[cfg(feature = "pci")]
+    fn pci_driver(
+        &mut self,
+        driver: alloc::boxed::Box<dyn crate::device::pci::PciDriver>,
+    ) -> Result<(), crate::device::pci::error::PciError> {
+        use crate::device::DeviceHub;
+        use crate::device::pci::PCI_HUB;
+
+        let name = driver.name();
+
+        PCI_HUB.get().run_mut(|hub| hub.register(driver))?;
+
+        self.pci_drivers.push(name);
+
+        Ok(())
+    }
+}
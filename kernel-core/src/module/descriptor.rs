@@ -0,0 +1,166 @@
+use crate::module::context::ModuleContext;
+
+/// The magic number every [ModuleDescriptor] starts with.
+pub const MODULE_MAGIC: u64 = u64::from_le_bytes(*b"SATOMMOD");
+
+/// The version of the module ABI.
+///
+/// Must be increased whenever the layout of the [ModuleDescriptor]
+/// or the signature of an exported kernel symbol changes.
+pub const MODULE_ABI_VERSION: u32 = 9;
+
+/// The `init` hook of a [ModuleDescriptor].
+pub type ModuleInitFn = extern "C" fn(&mut ModuleContext) -> ModuleStatus;
+
+/// The `update` and `exit` hooks of a [ModuleDescriptor].
+pub type ModuleHookFn = extern "C" fn();
+
+/// The descriptor a kernel module exports as its `KERNEL_MODULE` symbol.
+///
+/// The kernel checks the magic number, the ABI version and the size before reading any
+/// other field, so modules built against an incompatible `kernel-core` are rejected.
+/// Use [ModuleDescriptor::new] to fill in these fields.
+///
+/// The hooks use the `C` ABI and are nullable, so the kernel can reject missing or
+/// invalid hooks before calling them.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct ModuleDescriptor {
+    /// Must be [MODULE_MAGIC].
+    pub magic: u64,
+    /// Must be [MODULE_ABI_VERSION].
+    pub abi_version: u32,
+    /// Must be the size of the descriptor in bytes.
+    pub size: u32,
+    /// The name of the module.
+    pub name: ModuleStr,
+    /// The version of the module.
+    pub version: ModuleStr,
+    /// The author of the module.
+    pub author: ModuleStr,
+    /// A short description of the module.
+    pub description: ModuleStr,
+    /// The names of the modules or services the module requires to run.
+    ///
+    /// The module is only initialized after all of its dependencies are running.
+    pub dependencies: ModuleStrList,
+    /// The names of the services the module provides to other modules.
+    pub provides: ModuleStrList,
+    /// Called once after the module was loaded to register its extensions with the [ModuleContext].
+    /// A failed [ModuleStatus] marks the module as failed.
+    pub init: Option<ModuleInitFn>,
+    /// Called periodically while the module is running.
+    pub update: Option<ModuleHookFn>,
+    /// Called before a running module is unloaded.
+    pub exit: Option<ModuleHookFn>,
+}
+
+impl ModuleDescriptor {
+    /// The size of the fields that are validated before the rest of the descriptor is read.
+    pub const HEADER_SIZE: usize = size_of::<u64>() + 2 * size_of::<u32>();
+
+    /// Create a new descriptor for the current ABI version.
+    pub const fn new(
+        name: &'static str,
+        version: &'static str,
+        author: &'static str,
+        description: &'static str,
+        dependencies: &'static [ModuleStr],
+        provides: &'static [ModuleStr],
+        init: ModuleInitFn,
+        update: ModuleHookFn,
+        exit: ModuleHookFn,
+    ) -> Self {
+        Self {
+            magic: MODULE_MAGIC,
+            abi_version: MODULE_ABI_VERSION,
+            size: size_of::<Self>() as u32,
+            name: ModuleStr::new(name),
+            version: ModuleStr::new(version),
+            author: ModuleStr::new(author),
+            description: ModuleStr::new(description),
+            dependencies: ModuleStrList::new(dependencies),
+            provides: ModuleStrList::new(provides),
+            init: Some(init),
+            update: Some(update),
+            exit: Some(exit),
+        }
+    }
+}
+
+unsafe impl Sync for ModuleDescriptor {}
+
+/// The result of the `init` hook of a [ModuleDescriptor] with a stable layout.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct ModuleStatus {
+    /// Zero if the hook succeeded.
+    pub code: u32,
+    /// The error message if the hook failed, otherwise empty.
+    pub error: ModuleStr,
+}
+
+impl ModuleStatus {
+    /// The status of a successful hook.
+    pub const OK: Self = Self {
+        code: 0,
+        error: ModuleStr::new(""),
+    };
+
+    /// Create the status of a hook that failed with the given error message.
+    pub const fn error(error: &'static str) -> Self {
+        Self {
+            code: 1,
+            error: ModuleStr::new(error),
+        }
+    }
+}
+
+impl From<Result<(), &'static str>> for ModuleStatus {
+    fn from(result: Result<(), &'static str>) -> Self {
+        match result {
+            Ok(()) => Self::OK,
+            Err(err) => Self::error(err),
+        }
+    }
+}
+
+/// A string inside a [ModuleDescriptor] with a stable layout.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct ModuleStr {
+    /// Pointer to the UTF-8 bytes of the string.
+    pub ptr: *const u8,
+    /// Length of the string in bytes.
+    pub len: usize,
+}
+
+impl ModuleStr {
+    /// Create a new module string from a static string.
+    pub const fn new(s: &'static str) -> Self {
+        Self {
+            ptr: s.as_ptr(),
+            len: s.len(),
+        }
+    }
+}
+
+/// A list of [ModuleStr]s inside a [ModuleDescriptor] with a stable layout.
+#[repr(C)]
+#[derive(Copy, Clone, Debug)]
+pub struct ModuleStrList {
+    /// Pointer to the first string of the list.
+    pub ptr: *const ModuleStr,
+    /// Number of strings in the list.
+    pub len: usize,
+}
+
+impl ModuleStrList {
+    /// Create a new module string list from a static slice.
+    pub const fn new(list: &'static [ModuleStr]) -> Self {
+        Self {
+            ptr: list.as_ptr(),
+            len: list.len(),
+        }
+    }
+}
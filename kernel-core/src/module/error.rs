@@ -0,0 +1,97 @@
+use crate::module::descriptor::MODULE_ABI_VERSION;
+use alloc::string::String;
+use alloc::vec::Vec;
+use core::fmt::{Display, Formatter};
+
+/// Error type returned when loading a kernel module fails.
+#[derive(Debug)]
+pub enum ModuleError {
+    /// The file is not a valid ELF file.
+    InvalidElf,
+    /// The module does not contain any loadable segments.
+    NoLoadableSegments,
+    /// The module does not export a `KERNEL_MODULE` symbol.
+    MissingModuleSymbol,
+    /// The module descriptor does not start with the module magic number.
+    InvalidMagic(u64),
+    /// The module was built for the given, incompatible ABI version.
+    IncompatibleAbi(u32),
+    /// The size of the module descriptor does not match the kernel's descriptor.
+    InvalidDescriptorSize(u32),
+    /// The given field of the module descriptor is out of bounds or malformed.
+    InvalidDescriptor(&'static str),
+    /// No module source contains a module at the given path.
+    NotFound(String),
+    /// A module with the given name is already loaded.
+    AlreadyLoaded(String),
+    /// The integrity of the module could not be verified for the given reason.
+    VerificationFailed(&'static str),
+    /// No module with the given name is loaded.
+    NotLoaded(String),
+    /// The module can not be unloaded, since the given running modules depend on it.
+    InUse(Vec<String>),
+    /// The module can not be unloaded, since its command with the given name is executing
+    /// or its app with the given name is running.
+    Busy(&'static str),
+    /// There is not enough memory left to load the module.
+    OutOfMemory,
+    /// The module contains a relocation of an unsupported type.
+    UnsupportedRelocation(u32),
+    /// A relocation targets an address outside of the module image.
+    InvalidRelocation(u64),
+    /// The page at the given linked virtual address would be both writable and executable.
+    WritableAndExecutable(u64),
+    /// The permissions of the image page at the given address could not be changed.
+    ProtectionFailed(usize),
+    /// The module refers to symbols that are neither defined by itself nor exported by the kernel.
+    UnresolvedSymbols(Vec<String>),
+}
+
+impl Display for ModuleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ModuleError::InvalidElf => write!(f, "Invalid ELF file"),
+            ModuleError::NoLoadableSegments => write!(f, "No loadable segments"),
+            ModuleError::MissingModuleSymbol => write!(f, "Missing 'KERNEL_MODULE' symbol"),
+            ModuleError::InvalidMagic(magic) => {
+                write!(f, "Invalid module descriptor magic {magic:#x}")
+            }
+            ModuleError::IncompatibleAbi(version) => write!(
+                f,
+                "Incompatible module ABI version {version}, expected {MODULE_ABI_VERSION}"
+            ),
+            ModuleError::InvalidDescriptorSize(size) => {
+                write!(f, "Invalid module descriptor size {size}")
+            }
+            ModuleError::InvalidDescriptor(field) => {
+                write!(f, "Invalid module descriptor field '{field}'")
+            }
+            ModuleError::NotFound(path) => write!(f, "Module '{path}' not found"),
+            ModuleError::AlreadyLoaded(name) => write!(f, "Module '{name}' is already loaded"),
+            ModuleError::VerificationFailed(reason) => {
+                write!(f, "Verification failed: {reason}")
+            }
+            ModuleError::NotLoaded(name) => write!(f, "No module named '{name}' is loaded"),
+            ModuleError::InUse(dependents) => {
+                write!(f, "Module is used by: {}", dependents.join(", "))
+            }
+            ModuleError::Busy(name) => write!(f, "Module is busy running '{name}'"),
+            ModuleError::OutOfMemory => write!(f, "Out of memory"),
+            ModuleError::UnsupportedRelocation(kind) => {
+                write!(f, "Unsupported relocation type {kind}")
+            }
+            ModuleError::InvalidRelocation(offset) => {
+                write!(f, "Invalid relocation at offset {offset:#x}")
+            }
+            ModuleError::WritableAndExecutable(vaddr) => {
+                write!(f, "Page at {vaddr:#x} would be writable and executable")
+            }
+            ModuleError::ProtectionFailed(addr) => {
+                write!(f, "Failed to protect page at {addr:#x}")
+            }
+            ModuleError::UnresolvedSymbols(names) => {
+                write!(f, "Unresolved symbols: {}", names.join(", "))
+            }
+        }
+    }
+}
@@ -0,0 +1,156 @@
+use crate::api::{self, KernelApi};
+use crate::control::CONTROL;
+use crate::control::command::Command;
+use crate::export_symbol;
+use crate::ipc::error::IpcError;
+use crate::ipc::{self, Endpoint, Message};
+use core::alloc::Layout;
+use log::Level;
+
+/// Log a UTF-8 message with the given level, where `1` is error and `5` is trace.
+///
+/// # Safety
+/// `msg` must point to `len` readable bytes.
+pub unsafe extern "C" fn kernel_log(level: u8, msg: *const u8, len: usize) {
+    let level = match level {
+        1 => Level::Error,
+        2 => Level::Warn,
+        3 => Level::Info,
+        4 => Level::Debug,
+        _ => Level::Trace,
+    };
+
+    let msg = unsafe { core::slice::from_raw_parts(msg, len) };
+
+    log::log!(level, "{}", alloc::string::String::from_utf8_lossy(msg));
+}
+
+/// Allocate memory on the kernel heap. Returns null on failure.
+///
+/// # Safety
+/// See [core::alloc::GlobalAlloc::alloc].
+pub unsafe extern "C" fn kernel_alloc(size: usize, align: usize) -> *mut u8 {
+    match Layout::from_size_align(size, align) {
+        Ok(layout) => unsafe { api::memory().alloc(layout) },
+        Err(_) => core::ptr::null_mut(),
+    }
+}
+
+/// Allocate zeroed memory on the kernel heap. Returns null on failure.
+///
+/// # Safety
+/// See [core::alloc::GlobalAlloc::alloc_zeroed].
+pub unsafe extern "C" fn kernel_alloc_zeroed(size: usize, align: usize) -> *mut u8 {
+    match Layout::from_size_align(size, align) {
+        Ok(layout) => unsafe { api::memory().alloc_zeroed(layout) },
+        Err(_) => core::ptr::null_mut(),
+    }
+}
+
+/// Free memory allocated with [kernel_alloc] or [kernel_alloc_zeroed].
+///
+/// # Safety
+/// See [core::alloc::GlobalAlloc::dealloc].
+pub unsafe extern "C" fn kernel_dealloc(ptr: *mut u8, size: usize, align: usize) {
+    unsafe {
+        api::memory().dealloc(ptr, Layout::from_size_align_unchecked(size, align));
+    }
+}
+
+/// Resize memory allocated with [kernel_alloc] or [kernel_alloc_zeroed].
+///
+/// # Safety
+/// See [core::alloc::GlobalAlloc::realloc].
+pub unsafe extern "C" fn kernel_realloc(
+    ptr: *mut u8,
+    size: usize,
+    align: usize,
+    new_size: usize,
+) -> *mut u8 {
+    unsafe {
+        api::memory().realloc(
+            ptr,
+            Layout::from_size_align_unchecked(size, align),
+            new_size,
+        )
+    }
+}
+
+/// Returns the global [KernelApi].
+pub extern "Rust" fn kernel_api() -> KernelApi {
+    api::kernel()
+}
+
+/// Register a command for the control.
+///
+/// Returns `false` if a command with the same name is already registered.
+/// Prefer [ModuleContext::register_command](super::context::ModuleContext::register_command),
+/// which unregisters the command when the module is unloaded.
+pub extern "Rust" fn kernel_register_command(command: Command) -> bool {
+    CONTROL.get().register(command)
+}
+
+/// Register a PCI driver at the global PCI hub.
+///
+/// Returns `false` if a driver with the same name is already registered.
+/// Prefer [ModuleContext::register_pci_driver](super::context::ModuleContext::register_pci_driver),
+/// which unregisters the driver when the module is unloaded.
+#[cfg(feature = "pci")]
+pub extern "Rust" fn kernel_register_pci_driver(
+    driver: alloc::boxed::Box<dyn crate::device::pci::PciDriver>,
+) -> bool {
+    use crate::device::DeviceHub;
+    use crate::device::pci::PCI_HUB;
+
+    PCI_HUB.get().run_mut(|hub| hub.register(driver)).is_ok()
+}
+
+/// Create a new IPC channel and return both of its endpoints, see [Endpoint::pair].
+pub extern "Rust" fn kernel_ipc_channel() -> (Endpoint, Endpoint) {
+    Endpoint::pair()
+}
+
+/// Send a message through an IPC endpoint, see [Endpoint::send].
+pub extern "Rust" fn kernel_ipc_send(
+    endpoint: &Endpoint,
+    message: Message,
+) -> Result<(), IpcError> {
+    endpoint.send(message)
+}
+
+/// Receive the next message from an IPC endpoint without blocking, see [Endpoint::try_recv].
+pub extern "Rust" fn kernel_ipc_try_recv(endpoint: &Endpoint) -> Result<Message, IpcError> {
+    endpoint.try_recv()
+}
+
+/// Receive the next message from an IPC endpoint, blocking until one arrives,
+/// see [Endpoint::recv].
+pub extern "Rust" fn kernel_ipc_recv(endpoint: &Endpoint) -> Result<Message, IpcError> {
+    endpoint.recv()
+}
+
+/// Publish an IPC service with the given name and return its listener, see [ipc::publish].
+pub extern "Rust" fn kernel_ipc_publish(name: &str) -> Result<Endpoint, IpcError> {
+    ipc::publish(name)
+}
+
+/// Connect to the IPC service with the given name, see [ipc::connect].
+pub extern "Rust" fn kernel_ipc_connect(name: &str) -> Result<Endpoint, IpcError> {
+    ipc::connect(name)
+}
+
+export_symbol!(kernel_log);
+export_symbol!(kernel_alloc);
+export_symbol!(kernel_alloc_zeroed);
+export_symbol!(kernel_dealloc);
+export_symbol!(kernel_realloc);
+export_symbol!(kernel_api);
+export_symbol!(kernel_register_command);
+#[cfg(feature = "pci")]
+export_symbol!(kernel_register_pci_driver);
+export_symbol!(kernel_ipc_channel);
+export_symbol!(kernel_ipc_send);
+export_symbol!(kernel_ipc_try_recv);
+export_symbol!(kernel_ipc_recv);
+export_symbol!(kernel_ipc_publish);
+export_symbol!(kernel_ipc_connect);
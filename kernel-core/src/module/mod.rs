@@ -0,0 +1,833 @@
+use crate::config::BOOT_CONFIG;
+use crate::module::context::ModuleContext;
+use crate::module::descriptor::{
+    MODULE_ABI_VERSION, MODULE_MAGIC, ModuleDescriptor, ModuleHookFn, ModuleInitFn, ModuleStatus,
+    ModuleStr, ModuleStrList,
+};
+use crate::module::error::ModuleError;
+use crate::module::source::{LimineSource, ModuleSource};
+use crate::sync::rwlock::RwLock;
+use crate::{api, requests};
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+use core::alloc::Layout;
+use core::fmt::{Display, Formatter};
+use core::mem::offset_of;
+use object::elf::{
+    PF_W, PF_X, PT_GNU_RELRO, PT_LOAD, R_X86_64_64, R_X86_64_GLOB_DAT, R_X86_64_JUMP_SLOT,
+    R_X86_64_RELATIVE,
+};
+use object::read::elf::ProgramHeader;
+use object::{
+    File, Object, ObjectSegment, ObjectSymbol, ObjectSymbolTable, RelocationFlags, RelocationTarget,
+};
+
+/// Contains the [ModuleContext] modules register their extensions with.
+pub mod context;
+
+/// Contains the [ModuleDescriptor] modules export as their `KERNEL_MODULE` symbol.
+pub mod descriptor;
+
+/// Contains the [ModuleError] type.
+pub mod error;
+
+/// Contains the kernel functions exported to modules.
+///
+/// Functions with the `C` ABI only use FFI-safe types and can be used by any module.
+/// Functions with the `Rust` ABI take kernel types and require the module to be built
+/// with the same compiler and `kernel-core` version as the kernel.
+pub mod exports;
+
+/// Contains the [ModuleSource] trait modules are loaded from at runtime.
+pub mod source;
+
+/// Contains the integrity verification of modules before they are loaded.
+pub mod verify;
+
+/// Contains the [KernelSymbol](symbol::KernelSymbol) table modules are linked against.
+pub mod symbol;
+
+/// All loaded modules, including the ones that failed to initialize.
+///
+/// The lifecycle hooks of a module are called while this lock is held,
+/// so modules must not access it themselves.
+pub static MODULES: RwLock<Vec<LoadedModule>> = RwLock::new(Vec::new());
+
+/// The registered [ModuleSource]s, searched in order by [load].
+static SOURCES: RwLock<Vec<Box<dyn ModuleSource>>> = RwLock::new(Vec::new());
+
+const PAGE_SIZE: u64 = 4096;
+
+const CYCLIC_DEPENDENCY: &str = "cyclic dependency";
+
+/// Register the [LimineSource] and load all limine modules that are kernel modules.
+///
+/// Modules that fail to load are logged and skipped. Modules with the limine module string
+/// [LimineSource::MANUAL] are not loaded at boot, but can be loaded using [load].
+/// No modules are loaded at boot, if the [BootConfig](crate::config::BootConfig) disables them.
+/// The loaded modules are initialized afterward by [run_init].
+///
+/// # Safety
+/// This must only be called once after the global [BOOT_CONFIG] is initialized.
+pub unsafe fn init() {
+    register_source(LimineSource);
+
+    if !BOOT_CONFIG.get().modules {
+        log::info!("Skipping kernel modules, since they are disabled.");
+        return;
+    }
+
+    let Some(response) = requests::modules() else {
+        log::info!("No limine modules found.");
+        return;
+    };
+
+    for file in response.modules() {
+        // Executables and other files are shipped as limine modules as well
+        if !KernelModule::is_limine_module(file) {
+            log::info!(
+                "Skipping limine module {}, since it is not a kernel module.",
+                file.path().to_string_lossy()
+            );
+            continue;
+        }
+
+        if file.string().to_bytes() == LimineSource::MANUAL.as_bytes() {
+            log::info!(
+                "Skipping limine module {}, since it is loaded manually.",
+                file.path().to_string_lossy()
+            );
+            continue;
+        }
+
+        match KernelModule::load_limine(file).and_then(insert) {
+            Ok(name) => log::info!("Loaded limine module {name}"),
+            Err(err) => log::error!(
+                "Failed to load limine module {}: {err}",
+                file.path().to_string_lossy()
+            ),
+        }
+    }
+}
+
+/// Initialize all modules that were loaded, but not initialized yet, in dependency order.
+///
+/// A module is initialized once each of its dependencies is provided by a running module.
+/// Modules with missing, failed or cyclic dependencies are marked as failed.
+pub fn run_init() {
+    MODULES.run_mut(|modules| {
+        while let Some(index) = next_ready(modules) {
+            modules[index].start();
+        }
+
+        // The remaining modules wait for dependencies that will never run.
+        // Missing and failed dependencies are reported first, so only actual cycles remain.
+        while let Some((index, (reason, dependency))) = modules
+            .iter()
+            .enumerate()
+            .filter(|(_, loaded)| loaded.state == ModuleState::Loaded)
+            .map(|(index, loaded)| (index, unresolved_dependency(modules, &loaded.module)))
+            .min_by_key(|(_, (reason, _))| *reason == CYCLIC_DEPENDENCY)
+        {
+            log::error!(
+                "Failed to initialize module {}: {reason} '{dependency}'",
+                modules[index].module.name
+            );
+
+            modules[index].state = ModuleState::Failed(reason);
+        }
+    });
+}
+
+/// Returns the index of the next loaded module whose dependencies are all running.
+fn next_ready(modules: &[LoadedModule]) -> Option<usize> {
+    modules.iter().position(|loaded| {
+        loaded.state == ModuleState::Loaded
+            && loaded.module.dependencies.iter().all(|dependency| {
+                providers(modules, dependency).any(|state| state == ModuleState::Running)
+            })
+    })
+}
+
+/// Returns the states of all modules satisfying the given dependency.
+fn providers<'a>(
+    modules: &'a [LoadedModule],
+    dependency: &'a str,
+) -> impl Iterator<Item = ModuleState> + 'a {
+    modules
+        .iter()
+        .filter(move |loaded| loaded.module.satisfies(dependency))
+        .map(LoadedModule::state)
+}
+
+/// Returns why the given module can not be initialized, together with the responsible dependency.
+fn unresolved_dependency(
+    modules: &[LoadedModule],
+    module: &KernelModule,
+) -> (&'static str, String) {
+    let mut cyclic = None;
+
+    for dependency in &module.dependencies {
+        let states = providers(modules, dependency).collect::<Vec<_>>();
+
+        if states.is_empty() {
+            return ("missing dependency", dependency.clone());
+        }
+
+        if states
+            .iter()
+            .all(|state| matches!(state, ModuleState::Failed(_)))
+        {
+            return ("failed dependency", dependency.clone());
+        }
+
+        if !states.contains(&ModuleState::Running) {
+            cyclic.get_or_insert(dependency);
+        }
+    }
+
+    (CYCLIC_DEPENDENCY, cyclic.cloned().unwrap_or_default())
+}
+
+/// Returns the names of the running modules that can not run without the module at the given index.
+fn dependents(modules: &[LoadedModule], index: usize) -> Vec<String> {
+    let is_provided_by_other = |dependency: &str| {
+        modules.iter().enumerate().any(|(i, other)| {
+            i != index && other.state == ModuleState::Running && other.module.satisfies(dependency)
+        })
+    };
+
+    modules
+        .iter()
+        .enumerate()
+        .filter(|(i, loaded)| *i != index && loaded.state == ModuleState::Running)
+        .filter(|(_, loaded)| {
+            loaded.module.dependencies.iter().any(|dependency| {
+                modules[index].module.satisfies(dependency) && !is_provided_by_other(dependency)
+            })
+        })
+        .map(|(_, loaded)| loaded.module.name.clone())
+        .collect()
+}
+
+/// Update all running modules.
+pub fn run_update() {
+    MODULES.run(|modules| {
+        modules
+            .iter()
+            .filter(|loaded| loaded.state == ModuleState::Running)
+            .for_each(|loaded| loaded.module.update())
+    });
+}
+
+/// Register a new source modules can be loaded from.
+pub fn register_source(source: impl ModuleSource + 'static) {
+    SOURCES.run_mut(|sources| sources.push(Box::new(source)));
+}
+
+/// Load the module at the given path from the first [ModuleSource] containing it and initialize it.
+///
+/// Returns the name of the module. A module that fails to initialize stays loaded as failed,
+/// which includes modules with dependencies that are not running.
+pub fn load(path: &str) -> Result<String, ModuleError> {
+    let bytes = SOURCES
+        .run(|sources| sources.iter().find_map(|source| source.read(path)))
+        .ok_or_else(|| ModuleError::NotFound(path.to_string()))?;
+
+    let name = insert(KernelModule::load(bytes)?)?;
+
+    run_init();
+
+    Ok(name)
+}
+
+/// Add a loaded module to [MODULES] and return its name.
+fn insert(loaded: LoadedModule) -> Result<String, ModuleError> {
+    let name = loaded.module.name.clone();
+
+    MODULES.run_mut(|modules| {
+        if modules.iter().any(|other| other.module.name == name) {
+            return Err(ModuleError::AlreadyLoaded(name.clone()));
+        }
+
+        modules.push(loaded);
+
+        Ok(())
+    })?;
+
+    Ok(name)
+}
+
+/// Unload the module with the given name.
+///
+/// Calls the `exit` hook of the module if it is running, unregisters everything it
+/// registered through its [ModuleContext] and frees its memory afterward.
+/// Modules can only be unloaded after all running modules depending on them were unloaded
+/// and while none of their commands is executing and none of their apps is running.
+pub fn unload(name: &str) -> Result<(), ModuleError> {
+    let loaded = MODULES.run_mut(|modules| {
+        let index = modules
+            .iter()
+            .position(|loaded| loaded.module.name == name)
+            .ok_or_else(|| ModuleError::NotLoaded(name.to_string()))?;
+
+        let dependents = dependents(modules, index);
+
+        if !dependents.is_empty() {
+            return Err(ModuleError::InUse(dependents));
+        }
+
+        if let Some(busy) = modules[index].context.busy() {
+            return Err(ModuleError::Busy(busy));
+        }
+
+        let mut loaded = modules.remove(index);
+
+        if loaded.state == ModuleState::Running {
+            loaded.module.exit();
+        }
+
+        // Nothing may refer to the module anymore once its image is freed
+        loaded.context.unregister_all();
+
+        Ok(loaded)
+    })?;
+
+    // The image is freed outside the lock
+    drop(loaded);
+
+    log::info!("Unloaded module {name}");
+
+    Ok(())
+}
+
+/// A kernel module, read from a validated [ModuleDescriptor].
+#[derive(Clone, Debug)]
+pub struct KernelModule {
+    /// The unique name of the module.
+    pub name: String,
+    /// The version of the module.
+    pub version: String,
+    /// The author of the module.
+    pub author: String,
+    /// A short description of the module.
+    pub description: String,
+    /// The names of the modules or services this module requires to run.
+    pub dependencies: Vec<String>,
+    /// The names of the services this module provides to other modules.
+    pub provides: Vec<String>,
+    /// Called once after the module was loaded. A failed status marks the module as failed.
+    pub init: ModuleInitFn,
+    /// Called periodically while the module is running.
+    pub update: ModuleHookFn,
+    /// Called before a running module is unloaded.
+    pub exit: ModuleHookFn,
+}
+
+impl KernelModule {
+    /// Returns if the given limine module is a kernel module exporting a `KERNEL_MODULE` symbol.
+    pub fn is_limine_module(module: &limine::file::File) -> bool {
+        let bytes = unsafe {
+            core::slice::from_raw_parts(module.addr() as *const u8, module.size() as usize)
+        };
+
+        File::parse(bytes).is_ok_and(|file| {
+            file.dynamic_symbols()
+                .chain(file.symbols())
+                .any(|symbol| symbol.name() == Ok("KERNEL_MODULE"))
+        })
+    }
+
+    /// Load a module from the given limine module, see [KernelModule::load].
+    pub fn load_limine(module: &limine::file::File) -> Result<LoadedModule, ModuleError> {
+        log::info!(
+            "Loading internal limine module {}...",
+            module.path().to_string_lossy()
+        );
+        let addr = module.addr() as *const u8;
+        let size = module.size() as usize;
+
+        let bytes = unsafe { core::slice::from_raw_parts(addr, size) };
+
+        KernelModule::load(bytes)
+    }
+
+    /// Load a module from the given ELF file.
+    ///
+    /// All loadable segments are placed into one contiguous image at their offsets
+    /// relative to the lowest segment, with the remaining memory zero-filled.
+    /// Dynamic relocations are applied against the image afterward,
+    /// before its pages are protected according to their segments, see [ModuleImage::protect].
+    /// The integrity of the module is checked before it is parsed, see [verify::verify].
+    pub fn load(bytes: impl AsRef<[u8]>) -> Result<LoadedModule, ModuleError> {
+        let bytes = verify::verify(bytes.as_ref())?;
+        let file = File::parse(bytes).map_err(|_| ModuleError::InvalidElf)?;
+
+        let segments = file
+            .segments()
+            .filter(|segment| segment.size() > 0)
+            .collect::<Vec<_>>();
+
+        let start = segments
+            .iter()
+            .map(|segment| segment.address())
+            .min()
+            .ok_or(ModuleError::NoLoadableSegments)?
+            & !(PAGE_SIZE - 1);
+
+        let end = segments
+            .iter()
+            .map(|segment| segment.address().checked_add(segment.size()))
+            .try_fold(0, |end, segment_end| {
+                segment_end.map(|segment_end| end.max(segment_end))
+            })
+            .ok_or(ModuleError::InvalidElf)?;
+
+        let layout = Layout::from_size_align(
+            (end - start).next_multiple_of(PAGE_SIZE) as usize,
+            PAGE_SIZE as usize,
+        )
+        .map_err(|_| ModuleError::InvalidElf)?;
+
+        let image = unsafe { api::memory().alloc_zeroed(layout) };
+
+        if image.is_null() {
+            return Err(ModuleError::OutOfMemory);
+        }
+
+        // Freed again by dropping the image on failure
+        let image = ModuleImage {
+            addr: image as usize,
+            layout,
+            bias: (image as u64).wrapping_sub(start),
+        };
+
+        // Copy the file data of each segment, the rest including BSS stays zeroed
+        for segment in segments {
+            let data = segment.data().map_err(|_| ModuleError::InvalidElf)?;
+
+            if data.len() as u64 > segment.size() {
+                return Err(ModuleError::InvalidElf);
+            }
+
+            unsafe {
+                core::ptr::copy_nonoverlapping(
+                    data.as_ptr(),
+                    image.ptr(segment.address()),
+                    data.len(),
+                );
+            }
+        }
+
+        image.relocate(&file)?;
+        image.protect(&file)?;
+
+        let module_symbol = file
+            .dynamic_symbols()
+            .chain(file.symbols())
+            .find(|s| s.name() == Ok("KERNEL_MODULE") && s.is_definition())
+            .ok_or(ModuleError::MissingModuleSymbol)?;
+
+        let module = KernelModule::from_descriptor(&image, module_symbol.address())?;
+
+        Ok(LoadedModule {
+            module,
+            image,
+            state: ModuleState::Loaded,
+            context: ModuleContext::new(),
+        })
+    }
+
+    /// Read and validate the [ModuleDescriptor] at the given linked virtual address of the image.
+    fn from_descriptor(image: &ModuleImage, vaddr: u64) -> Result<Self, ModuleError> {
+        if !image.contains(vaddr, ModuleDescriptor::HEADER_SIZE as u64) {
+            return Err(ModuleError::InvalidDescriptor("header"));
+        }
+
+        let ptr = image.ptr(vaddr);
+
+        // Only the header is read until the layout is known to match
+        let (magic, abi_version, size) = unsafe {
+            (
+                (ptr as *const u64).read_unaligned(),
+                (ptr.add(offset_of!(ModuleDescriptor, abi_version)) as *const u32).read_unaligned(),
+                (ptr.add(offset_of!(ModuleDescriptor, size)) as *const u32).read_unaligned(),
+            )
+        };
+
+        if magic != MODULE_MAGIC {
+            return Err(ModuleError::InvalidMagic(magic));
+        }
+
+        if abi_version != MODULE_ABI_VERSION {
+            return Err(ModuleError::IncompatibleAbi(abi_version));
+        }
+
+        if size as usize != size_of::<ModuleDescriptor>() || !image.contains(vaddr, size as u64) {
+            return Err(ModuleError::InvalidDescriptorSize(size));
+        }
+
+        // Every bit pattern is valid for the fields, since the hooks are nullable
+        let descriptor = unsafe { (ptr as *const ModuleDescriptor).read_unaligned() };
+
+        let init = descriptor
+            .init
+            .filter(|&init| image.contains_addr(init as usize, 1))
+            .ok_or(ModuleError::InvalidDescriptor("init"))?;
+        let update = descriptor
+            .update
+            .filter(|&update| image.contains_addr(update as usize, 1))
+            .ok_or(ModuleError::InvalidDescriptor("update"))?;
+        let exit = descriptor
+            .exit
+            .filter(|&exit| image.contains_addr(exit as usize, 1))
+            .ok_or(ModuleError::InvalidDescriptor("exit"))?;
+
+        Ok(Self {
+            name: image.read_str(descriptor.name, "name")?,
+            version: image.read_str(descriptor.version, "version")?,
+            author: image.read_str(descriptor.author, "author")?,
+            description: image.read_str(descriptor.description, "description")?,
+            dependencies: image.read_str_list(descriptor.dependencies, "dependencies")?,
+            provides: image.read_str_list(descriptor.provides, "provides")?,
+            init,
+            update,
+            exit,
+        })
+    }
+
+    /// Returns if this module is the given dependency or provides it.
+    pub fn satisfies(&self, dependency: &str) -> bool {
+        self.name == dependency || self.provides.iter().any(|service| service == dependency)
+    }
+
+    /// Call the `init` hook of the module with the given context.
+    pub fn init(&self, context: &mut ModuleContext) -> ModuleStatus {
+        (self.init)(context)
+    }
+
+    /// Call the `update` hook of the module.
+    pub fn update(&self) {
+        (self.update)()
+    }
+
+    /// Call the `exit` hook of the module.
+    pub fn exit(&self) {
+        (self.exit)()
+    }
+}
+
+/// The state of a [LoadedModule].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModuleState {
+    /// The module was loaded, but not initialized yet.
+    Loaded,
+    /// The module was initialized and is updated periodically.
+    Running,
+    /// The initialization of the module failed with the given error.
+    Failed(&'static str),
+}
+
+impl Display for ModuleState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Loaded => write!(f, "loaded"),
+            Self::Running => write!(f, "running"),
+            Self::Failed(err) => write!(f, "failed ({err})"),
+        }
+    }
+}
+
+/// A [KernelModule] together with the memory image it was loaded into.
+pub struct LoadedModule {
+    /// The module read from the descriptor.
+    pub module: KernelModule,
+    /// The memory image of the module, which is freed when the module is dropped.
+    pub image: ModuleImage,
+    state: ModuleState,
+    context: ModuleContext,
+}
+
+impl LoadedModule {
+    /// Returns the state of the module.
+    pub fn state(&self) -> ModuleState {
+        self.state
+    }
+
+    /// Initialize the module if it was not initialized yet.
+    pub fn start(&mut self) {
+        if self.state != ModuleState::Loaded {
+            return;
+        }
+
+        let status = self.module.init(&mut self.context);
+
+        let result = match status.code {
+            0 => Ok(()),
+            _ => Err(self
+                .image
+                .static_str(status.error)
+                .unwrap_or("invalid error message")),
+        };
+
+        self.state = match result {
+            Ok(()) => {
+                log::info!("Initialized module {}", self.module.name);
+                ModuleState::Running
+            }
+            Err(err) => {
+                log::error!("Failed to initialize module {}: {err}", self.module.name);
+                self.context.unregister_all();
+                ModuleState::Failed(err)
+            }
+        };
+    }
+}
+
+/// The contiguous memory a module is loaded into. The memory is freed when dropped.
+pub struct ModuleImage {
+    addr: usize,
+    layout: Layout,
+    /// The difference between the load address and the linked virtual addresses.
+    bias: u64,
+}
+
+impl ModuleImage {
+    /// Returns the address of the image.
+    pub fn addr(&self) -> usize {
+        self.addr
+    }
+
+    /// Returns the size of the image in bytes.
+    pub fn size(&self) -> usize {
+        self.layout.size()
+    }
+
+    /// Returns if `len` bytes at the given linked virtual address lie inside the image.
+    pub fn contains(&self, vaddr: u64, len: u64) -> bool {
+        self.contains_addr(vaddr.wrapping_add(self.bias) as usize, len as usize)
+    }
+
+    /// Returns if `len` bytes at the given address lie inside the image.
+    pub fn contains_addr(&self, addr: usize, len: usize) -> bool {
+        addr >= self.addr
+            && addr
+                .checked_add(len)
+                .is_some_and(|end| end <= self.addr + self.size())
+    }
+
+    /// Copy a [ModuleStr] that must lie inside the image.
+    ///
+    /// The `field` is reported as an invalid descriptor field if the string is out of
+    /// bounds or not valid UTF-8.
+    fn read_str(&self, s: ModuleStr, field: &'static str) -> Result<String, ModuleError> {
+        self.static_str(s)
+            .map(ToString::to_string)
+            .ok_or(ModuleError::InvalidDescriptor(field))
+    }
+
+    /// Returns a [ModuleStr] that must lie inside the image and be valid UTF-8.
+    ///
+    /// The string is only valid as long as the image, which the caller must ensure.
+    fn static_str(&self, s: ModuleStr) -> Option<&'static str> {
+        if !self.contains_addr(s.ptr as usize, s.len) {
+            return None;
+        }
+
+        let bytes = unsafe { core::slice::from_raw_parts(s.ptr, s.len) };
+
+        core::str::from_utf8(bytes).ok()
+    }
+
+    /// Copy all strings of a [ModuleStrList] that must lie inside the image.
+    fn read_str_list(
+        &self,
+        list: ModuleStrList,
+        field: &'static str,
+    ) -> Result<Vec<String>, ModuleError> {
+        let size = list
+            .len
+            .checked_mul(size_of::<ModuleStr>())
+            .ok_or(ModuleError::InvalidDescriptor(field))?;
+
+        if !self.contains_addr(list.ptr as usize, size) {
+            return Err(ModuleError::InvalidDescriptor(field));
+        }
+
+        (0..list.len)
+            .map(|i| self.read_str(unsafe { list.ptr.add(i).read_unaligned() }, field))
+            .collect()
+    }
+
+    /// Translate a linked virtual address to a pointer into the image.
+    fn ptr(&self, vaddr: u64) -> *mut u8 {
+        vaddr.wrapping_add(self.bias) as *mut u8
+    }
+
+    /// Apply all dynamic relocations of the given file.
+    ///
+    /// Undefined symbols are resolved against the exported kernel [symbol] table.
+    fn relocate(&self, file: &File) -> Result<(), ModuleError> {
+        // Collect all unresolved symbols up front to report them at once
+        let unresolved = file
+            .dynamic_symbols()
+            .filter(|symbol| symbol.is_undefined() && !symbol.is_weak())
+            .filter_map(|symbol| symbol.name().ok())
+            .filter(|name| !name.is_empty() && symbol::lookup(name).is_none())
+            .map(ToString::to_string)
+            .collect::<Vec<_>>();
+
+        if !unresolved.is_empty() {
+            return Err(ModuleError::UnresolvedSymbols(unresolved));
+        }
+
+        let Some(relocations) = file.dynamic_relocations() else {
+            return Ok(());
+        };
+
+        let symbols = file.dynamic_symbol_table();
+
+        for (offset, relocation) in relocations {
+            let RelocationFlags::Elf { r_type } = relocation.flags() else {
+                return Err(ModuleError::InvalidElf);
+            };
+
+            if !self.contains(offset, size_of::<u64>() as u64) {
+                return Err(ModuleError::InvalidRelocation(offset));
+            }
+
+            let addend = relocation.addend() as u64;
+
+            // The address of the referenced symbol
+            let resolve = || match relocation.target() {
+                RelocationTarget::Symbol(index) => {
+                    let symbol = symbols
+                        .as_ref()
+                        .and_then(|symbols| symbols.symbol_by_index(index).ok())
+                        .ok_or(ModuleError::InvalidElf)?;
+
+                    if symbol.is_undefined() {
+                        // Unresolved symbols were already reported, so only weak ones remain
+                        Ok(symbol
+                            .name()
+                            .ok()
+                            .and_then(symbol::lookup)
+                            .map_or(0, |addr| addr as u64))
+                    } else {
+                        Ok(symbol.address().wrapping_add(self.bias))
+                    }
+                }
+                _ => Err(ModuleError::UnsupportedRelocation(r_type)),
+            };
+
+            let value = match r_type {
+                R_X86_64_RELATIVE => self.bias.wrapping_add(addend),
+                R_X86_64_64 => resolve()?.wrapping_add(addend),
+                R_X86_64_GLOB_DAT | R_X86_64_JUMP_SLOT => resolve()?,
+                _ => return Err(ModuleError::UnsupportedRelocation(r_type)),
+            };
+
+            unsafe { (self.ptr(offset) as *mut u64).write_unaligned(value) };
+        }
+
+        Ok(())
+    }
+
+    /// Protect the pages of the image according to the program headers of the given file.
+    ///
+    /// Each page gets the permissions of the `PT_LOAD` segments it contains,
+    /// pages without any segment are read-only and never executable.
+    /// The pages completely covered by `PT_GNU_RELRO` are made read-only afterward,
+    /// so this must only be called after [ModuleImage::relocate].
+    /// Pages that would be both writable and executable are rejected.
+    fn protect(&self, file: &File) -> Result<(), ModuleError> {
+        let File::Elf64(elf) = file else {
+            return Err(ModuleError::InvalidElf);
+        };
+
+        let endian = elf.endian();
+        let headers = elf.elf_program_headers();
+
+        // The writable and executable permissions of each page
+        let mut pages = alloc::vec![(false, false); self.size() / PAGE_SIZE as usize];
+
+        for header in headers {
+            let vaddr = header.p_vaddr(endian);
+            let size = header.p_memsz(endian);
+
+            if size == 0 {
+                continue;
+            }
+
+            if !self.contains(vaddr, size) {
+                return Err(ModuleError::InvalidElf);
+            }
+
+            let offset = self.offset(vaddr);
+
+            match header.p_type(endian) {
+                PT_LOAD => {
+                    let flags = header.p_flags(endian);
+                    let first = offset / PAGE_SIZE;
+                    let last = (offset + size).div_ceil(PAGE_SIZE);
+
+                    for page in &mut pages[first as usize..last as usize] {
+                        page.0 |= flags & PF_W != 0;
+                        page.1 |= flags & PF_X != 0;
+                    }
+                }
+                PT_GNU_RELRO => {
+                    // Partially covered pages may contain writable data
+                    let first = offset.div_ceil(PAGE_SIZE);
+                    let last = (offset + size) / PAGE_SIZE;
+
+                    for page in &mut pages[first as usize..last.max(first) as usize] {
+                        page.0 = false;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        if let Some(index) = pages
+            .iter()
+            .position(|&(writable, executable)| writable && executable)
+        {
+            let vaddr = (self.addr as u64 + index as u64 * PAGE_SIZE).wrapping_sub(self.bias);
+
+            return Err(ModuleError::WritableAndExecutable(vaddr));
+        }
+
+        let mut addr = self.addr;
+
+        // Protect runs of pages with the same permissions at once
+        for run in pages.chunk_by(|a, b| a == b) {
+            let (writable, executable) = run[0];
+            let size = run.len() * PAGE_SIZE as usize;
+
+            if !unsafe { api::memory().protect(addr, size, writable, executable) } {
+                return Err(ModuleError::ProtectionFailed(addr));
+            }
+
+            addr += size;
+        }
+
+        Ok(())
+    }
+
+    /// Translate a linked virtual address to an offset into the image.
+    fn offset(&self, vaddr: u64) -> u64 {
+        vaddr.wrapping_add(self.bias) - self.addr as u64
+    }
+}
+
+impl Drop for ModuleImage {
+    fn drop(&mut self) {
+        unsafe {
+            // Return writable memory to the heap
+            api::memory().protect(self.addr, self.size(), true, false);
+            api::memory().dealloc(self.addr as *mut u8, self.layout);
+        }
+    }
+}
@@ -0,0 +1,39 @@
+use crate::requests;
+use alloc::vec::Vec;
+
+/// A source kernel modules can be loaded from at runtime, see [load](super::load).
+pub trait ModuleSource: Send + Sync {
+    /// Returns the name of the source.
+    fn name(&self) -> &str;
+
+    /// Read the module file at the given path.
+    ///
+    /// Returns [None] if the source does not contain the file.
+    fn read(&self, path: &str) -> Option<Vec<u8>>;
+}
+
+/// Loads modules from the files shipped as limine modules.
+///
+/// This includes limine modules that were not loaded at boot,
+/// because their module string is [LimineSource::MANUAL].
+pub struct LimineSource;
+
+impl LimineSource {
+    /// The limine module string of kernel modules that should not be loaded at boot.
+    pub const MANUAL: &'static str = "manual";
+}
+
+impl ModuleSource for LimineSource {
+    fn name(&self) -> &str {
+        "limine"
+    }
+
+    fn read(&self, path: &str) -> Option<Vec<u8>> {
+        let file = requests::module_file(path)?;
+
+        let bytes =
+            unsafe { core::slice::from_raw_parts(file.addr() as *const u8, file.size() as usize) };
+
+        Some(bytes.to_vec())
+    }
+}
@@ -0,0 +1,78 @@
+/// A kernel symbol that modules can link against.
+///
+/// Created by the [export_symbol](crate::export_symbol) macro and placed into the
+/// `.kernel_symbols` linker section, which is collected by the kernel linker script.
+#[repr(C)]
+pub struct KernelSymbol {
+    name: &'static str,
+    addr: *const (),
+}
+
+// SAFETY: symbols are immutable and only point to functions or statics.
+unsafe impl Sync for KernelSymbol {}
+
+impl KernelSymbol {
+    /// Create a new symbol with the given name and address.
+    pub const fn new(name: &'static str, addr: *const ()) -> Self {
+        Self { name, addr }
+    }
+
+    /// Returns the name of the symbol.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the address of the symbol.
+    pub fn addr(&self) -> usize {
+        self.addr as usize
+    }
+}
+
+unsafe extern "C" {
+    static __kernel_symbols_start: KernelSymbol;
+    static __kernel_symbols_end: KernelSymbol;
+}
+
+/// Returns all exported kernel symbols.
+pub fn symbols() -> &'static [KernelSymbol] {
+    unsafe {
+        let start = &raw const __kernel_symbols_start;
+        let end = &raw const __kernel_symbols_end;
+
+        core::slice::from_raw_parts(start, end.offset_from(start) as usize)
+    }
+}
+
+/// Returns the address of the exported kernel symbol with the given name.
+pub fn lookup(name: &str) -> Option<usize> {
+    symbols()
+        .iter()
+        .find(|symbol| symbol.name == name)
+        .map(KernelSymbol::addr)
+}
+
+/// Export a function or static as [KernelSymbol], so modules can link against it.
+///
+/// The symbol name defaults to the identifier, but can be set with `as "name"`.
+/// Exported functions should use the `C` ABI, unless modules are built
+/// with the same compiler version as the kernel.
+///
+/// ```ignore
+/// export_symbol!(kernel_log);
+/// export_symbol!(crate::api::kernel as "kernel_api");
+/// ```
+#[macro_export]
+macro_rules! export_symbol {
+    ($name:ident) => {
+        $crate::export_symbol!($name as stringify!($name));
+    };
+
+    ($path:path as $name:expr) => {
+        const _: () = {
+            #[used]
+            #[unsafe(link_section = ".kernel_symbols")]
+            static SYMBOL: $crate::module::symbol::KernelSymbol =
+                $crate::module::symbol::KernelSymbol::new($name, $path as *const ());
+        };
+    };
+}
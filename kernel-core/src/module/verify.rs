@@ -0,0 +1,93 @@
+use crate::module::error::ModuleError;
+
+include!(concat!(env!("OUT_DIR"), "/module_verify.rs"));
+
+/// The magic number appended after the Ed25519 signature of a signed module.
+///
+/// A signed module consists of the module file, the 64 byte signature of the file and this magic.
+pub const SIGNATURE_MAGIC: &[u8; 8] = b"SATOMSIG";
+
+/// The size of an Ed25519 signature in bytes.
+pub const SIGNATURE_SIZE: usize = 64;
+
+/// Split a module into the module file and its signature, if it is signed.
+pub fn split_signature(bytes: &[u8]) -> (&[u8], Option<&[u8; SIGNATURE_SIZE]>) {
+    let Some(rest) = bytes.strip_suffix(SIGNATURE_MAGIC) else {
+        return (bytes, None);
+    };
+
+    match rest.split_last_chunk::<SIGNATURE_SIZE>() {
+        Some((file, signature)) => (file, Some(signature)),
+        None => (bytes, None),
+    }
+}
+
+/// Verify the integrity of a module and return the module file without its signature.
+///
+/// A module is trusted, if the SHA-256 hash of the module file is part of the [ALLOWLIST]
+/// or if it is signed with the [PUBLIC_KEY], depending on the enabled verification features.
+/// Untrusted modules are rejected if the `module-verify-enforce` feature is enabled,
+/// otherwise a warning is logged. Without any verification feature, all modules are trusted.
+pub fn verify(bytes: &[u8]) -> Result<&[u8], ModuleError> {
+    let (file, signature) = split_signature(bytes);
+
+    match check(file, signature) {
+        Ok(()) => Ok(file),
+        Err(reason) if cfg!(feature = "module-verify-enforce") => {
+            Err(ModuleError::VerificationFailed(reason))
+        }
+        Err(reason) => {
+            log::warn!("Module verification failed: {reason}. Loading the module anyway.");
+            Ok(file)
+        }
+    }
+}
+
+#[cfg(not(any(feature = "module-verify-sha256", feature = "module-verify-ed25519")))]
+fn check(_file: &[u8], _signature: Option<&[u8; SIGNATURE_SIZE]>) -> Result<(), &'static str> {
+    Ok(())
+}
+
+#[cfg(any(feature = "module-verify-sha256", feature = "module-verify-ed25519"))]
+fn check(file: &[u8], signature: Option<&[u8; SIGNATURE_SIZE]>) -> Result<(), &'static str> {
+    if is_allowlisted(file) {
+        return Ok(());
+    }
+
+    match signature {
+        Some(signature) => check_signature(file, signature),
+        None => Err("module is neither allowlisted nor signed"),
+    }
+}
+
+#[cfg(feature = "module-verify-sha256")]
+fn is_allowlisted(file: &[u8]) -> bool {
+    use sha2::{Digest, Sha256};
+
+    let hash: [u8; 32] = Sha256::digest(file).into();
+
+    ALLOWLIST.contains(&hash)
+}
+
+#[cfg(not(feature = "module-verify-sha256"))]
+fn is_allowlisted(_file: &[u8]) -> bool {
+    false
+}
+
+#[cfg(feature = "module-verify-ed25519")]
+fn check_signature(file: &[u8], signature: &[u8; SIGNATURE_SIZE]) -> Result<(), &'static str> {
+    use ed25519_dalek::{Signature, VerifyingKey};
+
+    let key = PUBLIC_KEY
+        .and_then(|key| VerifyingKey::from_bytes(&key).ok())
+        .ok_or("no valid public key for module signatures is built in")?;
+
+    key.verify_strict(file, &Signature::from_bytes(signature))
+        .map_err(|_| "invalid module signature")
+}
+
+/// Signed modules that are not allowlisted are rejected, since their signature can not be checked.
+#[cfg(not(feature = "module-verify-ed25519"))]
+fn check_signature(_file: &[u8], _signature: &[u8; SIGNATURE_SIZE]) -> Result<(), &'static str> {
+    Err("module is not allowlisted and signature verification is disabled")
+}
@@ -0,0 +1,218 @@
+use crate::api;
+use crate::process::error::ExecError;
+use crate::process::{PAGE_SIZE, PIE_BASE, STACK_SIZE, STACK_TOP, USER_START};
+use alloc::vec::Vec;
+use object::Endianness;
+use object::elf::{
+    EM_X86_64, ET_DYN, ET_EXEC, FileHeader64, PF_W, PF_X, PT_INTERP, PT_LOAD, PT_PHDR,
+};
+use object::read::elf::{FileHeader, ProgramHeader};
+
+/// A loadable segment of an [Executable].
+#[derive(Copy, Clone, Debug)]
+pub struct Segment<'a> {
+    /// The virtual address of the segment.
+    pub addr: usize,
+    /// The size of the segment in memory.
+    pub size: usize,
+    /// The file data of the segment. The remaining `size` is zero-filled.
+    pub data: &'a [u8],
+    /// If the segment is writable.
+    pub writable: bool,
+    /// If the segment is executable.
+    pub executable: bool,
+}
+
+impl Segment<'_> {
+    /// Returns if the given address lies inside this segment.
+    pub fn contains(&self, addr: usize) -> bool {
+        addr >= self.addr && addr < self.addr + self.size
+    }
+}
+
+/// A statically-linked or position independent ELF executable.
+///
+/// Position independent executables are loaded at [PIE_BASE].
+#[derive(Clone, Debug)]
+pub struct Executable<'a> {
+    entry: usize,
+    phdr: usize,
+    phent: usize,
+    phnum: usize,
+    segments: Vec<Segment<'a>>,
+}
+
+impl<'a> Executable<'a> {
+    /// Parse an executable from the given ELF file.
+    pub fn parse(bytes: &'a [u8]) -> Result<Self, ExecError> {
+        let header = FileHeader64::<Endianness>::parse(bytes).map_err(|_| ExecError::InvalidElf)?;
+        let endian = header.endian().map_err(|_| ExecError::InvalidElf)?;
+
+        if header.e_machine(endian) != EM_X86_64 {
+            return Err(ExecError::UnsupportedArchitecture);
+        }
+
+        let base = match header.e_type(endian) {
+            ET_EXEC => 0,
+            ET_DYN => PIE_BASE,
+            _ => return Err(ExecError::NotExecutable),
+        };
+
+        let headers = header
+            .program_headers(endian, bytes)
+            .map_err(|_| ExecError::InvalidElf)?;
+
+        let mut segments = Vec::with_capacity(headers.len());
+        let mut phdr = None;
+
+        for program in headers {
+            match program.p_type(endian) {
+                PT_INTERP => return Err(ExecError::DynamicallyLinked),
+
+                PT_PHDR => {
+                    phdr = Some(
+                        base.checked_add(program.p_vaddr(endian) as usize)
+                            .ok_or(ExecError::InvalidElf)?,
+                    )
+                }
+
+                PT_LOAD => {
+                    let addr = base
+                        .checked_add(program.p_vaddr(endian) as usize)
+                        .ok_or(ExecError::InvalidElf)?;
+                    let size = program.p_memsz(endian) as usize;
+                    let data = program
+                        .data(endian, bytes)
+                        .map_err(|_| ExecError::InvalidElf)?;
+
+                    let end = addr
+                        .checked_add(size)
+                        .ok_or(ExecError::InvalidSegment(addr))?;
+
+                    // Segments must not overlap the null page or the user stack
+                    if data.len() > size || addr < USER_START || end > STACK_TOP - STACK_SIZE {
+                        return Err(ExecError::InvalidSegment(addr));
+                    }
+
+                    let flags = program.p_flags(endian);
+
+                    segments.push(Segment {
+                        addr,
+                        size,
+                        data,
+                        writable: flags & PF_W != 0,
+                        executable: flags & PF_X != 0,
+                    });
+                }
+
+                _ => (),
+            }
+        }
+
+        if segments.is_empty() {
+            return Err(ExecError::NotExecutable);
+        }
+
+        // Without a PT_PHDR segment, find the program headers inside a loaded segment
+        let phdr = match phdr {
+            Some(phdr) => phdr,
+            None => {
+                let phoff = header.e_phoff(endian) as usize;
+
+                headers
+                    .iter()
+                    .filter(|program| program.p_type(endian) == PT_LOAD)
+                    .find_map(|program| {
+                        let relative = phoff.checked_sub(program.p_offset(endian) as usize)?;
+
+                        (relative < program.p_filesz(endian) as usize).then(|| {
+                            base.checked_add(program.p_vaddr(endian) as usize)
+                                .and_then(|addr| addr.checked_add(relative))
+                                .ok_or(ExecError::InvalidElf)
+                        })
+                    })
+                    .transpose()?
+                    .unwrap_or(0)
+            }
+        };
+
+        let entry = base
+            .checked_add(header.e_entry(endian) as usize)
+            .ok_or(ExecError::InvalidElf)?;
+
+        if !segments
+            .iter()
+            .any(|segment| segment.executable && segment.contains(entry))
+        {
+            return Err(ExecError::InvalidEntry(entry));
+        }
+
+        Ok(Self {
+            entry,
+            phdr,
+            phent: header.e_phentsize(endian) as usize,
+            phnum: headers.len(),
+            segments,
+        })
+    }
+
+    /// Map all loadable segments into the given address space.
+    ///
+    /// Returns the number of bytes mapped.
+    ///
+    /// # Safety
+    /// The address space must be valid.
+    pub unsafe fn load(&self, space: usize) -> Result<usize, ExecError> {
+        let mut mapped = 0;
+
+        for segment in &self.segments {
+            let start = segment.addr & !(PAGE_SIZE - 1);
+            let end = (segment.addr + segment.size).next_multiple_of(PAGE_SIZE);
+
+            unsafe {
+                if !api::process().map(
+                    space,
+                    start,
+                    end - start,
+                    segment.writable,
+                    segment.executable,
+                ) {
+                    return Err(ExecError::OutOfMemory);
+                }
+
+                if !api::process().write(space, segment.addr, segment.data) {
+                    return Err(ExecError::InvalidSegment(segment.addr));
+                }
+            }
+
+            mapped += end - start;
+        }
+
+        Ok(mapped)
+    }
+
+    /// Returns the entry point address.
+    pub fn entry(&self) -> usize {
+        self.entry
+    }
+
+    /// Returns the address of the program headers in memory or `0` if they are not loaded.
+    pub fn phdr(&self) -> usize {
+        self.phdr
+    }
+
+    /// Returns the size of a single program header.
+    pub fn phent(&self) -> usize {
+        self.phent
+    }
+
+    /// Returns the number of program headers.
+    pub fn phnum(&self) -> usize {
+        self.phnum
+    }
+
+    /// Returns the loadable segments.
+    pub fn segments(&self) -> &[Segment<'a>] {
+        &self.segments
+    }
+}
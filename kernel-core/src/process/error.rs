@@ -0,0 +1,42 @@
+use core::fmt::{Display, Formatter};
+
+/// Error type returned when loading or starting an executable fails.
+#[derive(Debug)]
+pub enum ExecError {
+    /// No executable was found at the given path.
+    NotFound,
+    /// The file is not a valid ELF file.
+    InvalidElf,
+    /// The executable was built for another architecture.
+    UnsupportedArchitecture,
+    /// The ELF file is not an executable, e.g. a relocatable object.
+    NotExecutable,
+    /// The executable requires a dynamic linker, which is not supported.
+    DynamicallyLinked,
+    /// A loadable segment lies outside the user address space.
+    InvalidSegment(usize),
+    /// The entry point does not lie inside an executable segment.
+    InvalidEntry(usize),
+    /// The arguments and environment do not fit on the user stack.
+    ArgumentsTooLong,
+    /// There is not enough memory left to load the executable.
+    OutOfMemory,
+}
+
+impl Display for ExecError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            ExecError::NotFound => write!(f, "Executable not found"),
+            ExecError::InvalidElf => write!(f, "Invalid ELF file"),
+            ExecError::UnsupportedArchitecture => write!(f, "Unsupported architecture"),
+            ExecError::NotExecutable => write!(f, "File is not an executable"),
+            ExecError::DynamicallyLinked => {
+                write!(f, "Dynamically linked executables are not supported")
+            }
+            ExecError::InvalidSegment(addr) => write!(f, "Invalid segment at {addr:#x}"),
+            ExecError::InvalidEntry(addr) => write!(f, "Invalid entry point at {addr:#x}"),
+            ExecError::ArgumentsTooLong => write!(f, "Arguments do not fit on the stack"),
+            ExecError::OutOfMemory => write!(f, "Out of memory"),
+        }
+    }
+}
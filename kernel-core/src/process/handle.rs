@@ -0,0 +1,62 @@
+use crate::ipc::Endpoint;
+use alloc::collections::BTreeMap;
+
+/// A handle referring to a kernel object owned by a process.
+///
+/// Handle `0` is never used, so it can act as invalid handle for user programs.
+pub type Handle = u64;
+
+/// The kernel objects a process holds, indexed by [Handle].
+pub struct HandleTable {
+    objects: BTreeMap<Handle, Endpoint>,
+    next: Handle,
+}
+
+impl HandleTable {
+    /// The maximum number of handles a single process can hold.
+    pub const MAX_HANDLES: usize = 256;
+
+    /// Create a new, empty handle table.
+    pub const fn new() -> Self {
+        Self {
+            objects: BTreeMap::new(),
+            next: 1,
+        }
+    }
+
+    /// Insert an endpoint and return its new handle.
+    ///
+    /// Returns [None] and drops the endpoint if the table is full.
+    pub fn insert(&mut self, endpoint: Endpoint) -> Option<Handle> {
+        if self.objects.len() >= Self::MAX_HANDLES {
+            return None;
+        }
+
+        let handle = self.next;
+        self.next += 1;
+
+        self.objects.insert(handle, endpoint);
+
+        Some(handle)
+    }
+
+    /// Returns the endpoint referred to by the given handle.
+    pub fn get(&self, handle: Handle) -> Option<&Endpoint> {
+        self.objects.get(&handle)
+    }
+
+    /// Remove the given handle from the table and return its endpoint.
+    pub fn remove(&mut self, handle: Handle) -> Option<Endpoint> {
+        self.objects.remove(&handle)
+    }
+
+    /// Returns the number of handles in the table.
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    /// Returns if the table holds no handles.
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+}
@@ -0,0 +1,296 @@
+use crate::api;
+use crate::process::elf::Executable;
+use crate::process::error::ExecError;
+use crate::process::handle::HandleTable;
+use crate::process::stack::UserStack;
+use crate::process::syscall::SyscallAction;
+use crate::process::table::{PROCESSES, Pid, ProcessState};
+use crate::requests;
+use alloc::boxed::Box;
+use alloc::string::{String, ToString};
+use alloc::vec::Vec;
+
+/// Contains the ELF [Executable] loader.
+pub mod elf;
+
+/// Contains the [ExecError] type.
+pub mod error;
+
+/// Contains the per-process [HandleTable].
+pub mod handle;
+
+/// Contains the [UserStack] builder.
+pub mod stack;
+
+/// Contains the system call interface for user programs.
+pub mod syscall;
+
+/// Contains the [ProcessTable](table::ProcessTable).
+pub mod table;
+
+/// The size of a page in user address spaces.
+pub const PAGE_SIZE: usize = 4096;
+
+/// The lowest address user programs may be loaded at. The first page is kept unmapped.
+pub const USER_START: usize = PAGE_SIZE;
+
+/// The address position independent executables are loaded at.
+pub const PIE_BASE: usize = 0x40_0000;
+
+/// The top of the user stack.
+pub const STACK_TOP: usize = 0x0000_7fff_ffff_f000;
+
+/// The size of the user stack in bytes.
+///
+/// As of right now, it's equal to 64 KB.
+pub const STACK_SIZE: usize = 64 * 1024;
+
+/// The environment passed to every user program.
+pub const DEFAULT_ENV: &[&str] = &["TERM=subatomic"];
+
+/// Load the executable at the given limine module path and add it to the [PROCESSES] table.
+///
+/// The path is passed as first argument, followed by `args`.
+/// Returns the [Pid] of the new process.
+pub fn spawn(path: &str, args: &[&str]) -> Result<Pid, ExecError> {
+    let file = requests::module_file(path).ok_or(ExecError::NotFound)?;
+    let bytes = unsafe { core::slice::from_raw_parts(file.addr(), file.size() as usize) };
+
+    let exe = Executable::parse(bytes)?;
+
+    let mut argv = Vec::with_capacity(args.len() + 1);
+    argv.push(path);
+    argv.extend_from_slice(args);
+
+    let process = Process::spawn(path, &exe, &argv, DEFAULT_ENV)?;
+
+    Ok(PROCESSES.run(|table| table.insert(process)))
+}
+
+/// Execute every ready process for one time slice.
+///
+/// Should be called in the kernel main loop.
+pub fn run_update() {
+    let ready = PROCESSES.run(|table| table.ready().collect::<Vec<_>>());
+
+    for pid in ready {
+        let Some(mut process) = PROCESSES.run(|table| table.take(pid)) else {
+            continue;
+        };
+
+        let start = api::process().ticks();
+        let slice = process.run_slice();
+        let ticks = api::process().ticks() - start;
+
+        let name = process.name().to_string();
+
+        if let Some(ProcessState::Exited(code)) =
+            PROCESSES.run(|table| table.put_back(pid, process, ticks, slice))
+        {
+            log::info!("Process '{name}' ({pid}) exited with code {code}.");
+        }
+    }
+}
+
+/// Returns the foreground job, if any.
+///
+/// The control does not execute commands while there is a foreground job.
+pub fn foreground() -> Option<Pid> {
+    PROCESSES.run(|table| table.foreground())
+}
+
+/// A user program in its own address space.
+pub struct Process {
+    name: String,
+    space: usize,
+    context: Box<UserContext>,
+    memory: usize,
+    handles: HandleTable,
+}
+
+impl Process {
+    /// Load the given executable into a new address space and prepare it for execution.
+    pub fn spawn(
+        name: &str,
+        exe: &Executable,
+        args: &[&str],
+        env: &[&str],
+    ) -> Result<Self, ExecError> {
+        let space = api::process()
+            .create_space()
+            .ok_or(ExecError::OutOfMemory)?;
+
+        // Dropping the process on failure frees the address space again
+        let mut process = Self {
+            name: name.to_string(),
+            space,
+            context: Box::default(),
+            memory: 0,
+            handles: HandleTable::new(),
+        };
+
+        let stack = UserStack::new(STACK_TOP, STACK_SIZE);
+
+        unsafe {
+            process.memory += exe.load(space)?;
+            process.memory += stack.map(space)?;
+
+            let stack_pointer = stack.build(space, exe, args, env)?;
+
+            *process.context = UserContext::new(exe.entry(), stack_pointer);
+        }
+
+        Ok(process)
+    }
+
+    /// Execute the process until it exits, blocks or is preempted by the timer.
+    pub fn run_slice(&mut self) -> Slice {
+        loop {
+            match unsafe { api::process().enter(self.space, &mut self.context) } {
+                Trap::Syscall => match syscall::handle(self) {
+                    SyscallAction::Continue => (),
+                    SyscallAction::Block => return Slice::Blocked,
+                    SyscallAction::Exit(code) => return Slice::Exited(code),
+                },
+
+                Trap::Timer => return Slice::Preempted,
+
+                Trap::Exception { name, addr } => {
+                    log::error!(
+                        "Process '{}' was terminated after a {name} at {addr:#x}.",
+                        self.name
+                    );
+
+                    return Slice::Exited(-1);
+                }
+            }
+        }
+    }
+
+    /// Returns the name of the process.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the number of bytes mapped into the address space of the process.
+    pub fn memory(&self) -> usize {
+        self.memory
+    }
+}
+
+impl Drop for Process {
+    fn drop(&mut self) {
+        unsafe { api::process().destroy_space(self.space) }
+    }
+}
+
+/// The outcome of executing a [Process] for one time slice.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Slice {
+    /// The time slice is over.
+    Preempted,
+    /// The process waits for a blocking system call to complete.
+    Blocked,
+    /// The process terminated with the given exit code.
+    Exited(i64),
+}
+
+/// The reason a user program returned control to the kernel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Trap {
+    /// The program issued a system call.
+    Syscall,
+    /// The program was preempted by the timer and its time slice is over.
+    Timer,
+    /// The program caused a CPU exception and can not continue.
+    Exception {
+        /// The name of the exception.
+        name: &'static str,
+        /// The faulting address, e.g. the accessed address of a page fault.
+        addr: usize,
+    },
+}
+
+/// The saved register state of a user program.
+///
+/// This is read and written by architecture specific assembly, so the layout must not change.
+#[derive(Debug, Clone, Default)]
+#[repr(C, align(16))]
+pub struct UserContext {
+    /// The saved floating point and SIMD state.
+    pub fpu: FpuState,
+    /// General purpose register `r15`.
+    pub r15: u64,
+    /// General purpose register `r14`.
+    pub r14: u64,
+    /// General purpose register `r13`.
+    pub r13: u64,
+    /// General purpose register `r12`.
+    pub r12: u64,
+    /// General purpose register `r11`.
+    pub r11: u64,
+    /// General purpose register `r10`.
+    pub r10: u64,
+    /// General purpose register `r9`.
+    pub r9: u64,
+    /// General purpose register `r8`.
+    pub r8: u64,
+    /// General purpose register `rbp`.
+    pub rbp: u64,
+    /// General purpose register `rdi`.
+    pub rdi: u64,
+    /// General purpose register `rsi`.
+    pub rsi: u64,
+    /// General purpose register `rdx`.
+    pub rdx: u64,
+    /// General purpose register `rcx`.
+    pub rcx: u64,
+    /// General purpose register `rbx`.
+    pub rbx: u64,
+    /// General purpose register `rax`.
+    pub rax: u64,
+    /// The instruction pointer.
+    pub rip: u64,
+    /// The code segment selector. Set by the architecture before entering user mode.
+    pub cs: u64,
+    /// The flags register.
+    pub rflags: u64,
+    /// The stack pointer.
+    pub rsp: u64,
+    /// The stack segment selector. Set by the architecture before entering user mode.
+    pub ss: u64,
+}
+
+impl UserContext {
+    /// The initial flags: interrupts enabled and the always-set reserved bit.
+    const INITIAL_FLAGS: u64 = 0x202;
+
+    /// Create a new context that starts executing at `entry` with the given stack pointer.
+    pub fn new(entry: usize, stack_pointer: usize) -> Self {
+        Self {
+            rip: entry as u64,
+            rflags: Self::INITIAL_FLAGS,
+            rsp: stack_pointer as u64,
+            ..Default::default()
+        }
+    }
+}
+
+/// The saved floating point and SIMD state in `fxsave` format.
+#[derive(Debug, Clone)]
+#[repr(C, align(16))]
+pub struct FpuState(pub [u8; 512]);
+
+impl Default for FpuState {
+    /// Returns the state after `fninit` with all SIMD exceptions masked.
+    fn default() -> Self {
+        const FCW: u16 = 0x037F;
+        const MXCSR: u32 = 0x1F80;
+
+        let mut state = [0; 512];
+        state[0..2].copy_from_slice(&FCW.to_le_bytes());
+        state[24..28].copy_from_slice(&MXCSR.to_le_bytes());
+
+        Self(state)
+    }
+}
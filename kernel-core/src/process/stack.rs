@@ -0,0 +1,141 @@
+use crate::api;
+use crate::process::PAGE_SIZE;
+use crate::process::elf::Executable;
+use crate::process::error::ExecError;
+use alloc::vec::Vec;
+
+/// Auxiliary vector entry types as defined by the System V ABI.
+mod auxv {
+    pub const AT_NULL: u64 = 0;
+    pub const AT_PHDR: u64 = 3;
+    pub const AT_PHENT: u64 = 4;
+    pub const AT_PHNUM: u64 = 5;
+    pub const AT_PAGESZ: u64 = 6;
+    pub const AT_BASE: u64 = 7;
+    pub const AT_ENTRY: u64 = 9;
+    pub const AT_UID: u64 = 11;
+    pub const AT_EUID: u64 = 12;
+    pub const AT_GID: u64 = 13;
+    pub const AT_EGID: u64 = 14;
+    pub const AT_SECURE: u64 = 23;
+    pub const AT_RANDOM: u64 = 25;
+}
+
+/// The initial stack of a user program.
+///
+/// Laid out as defined by the System V ABI, from the stack pointer upwards:
+/// `argc`, the `argv` pointers, the `envp` pointers, the auxiliary vector
+/// and finally the strings and random bytes they point to.
+pub struct UserStack {
+    top: usize,
+    size: usize,
+}
+
+impl UserStack {
+    /// Create a new stack description with the given top address and size in bytes.
+    pub const fn new(top: usize, size: usize) -> Self {
+        Self { top, size }
+    }
+
+    /// Map the stack memory into the given address space.
+    ///
+    /// Returns the number of bytes mapped.
+    ///
+    /// # Safety
+    /// The address space must be valid.
+    pub unsafe fn map(&self, space: usize) -> Result<usize, ExecError> {
+        if unsafe { api::process().map(space, self.top - self.size, self.size, true, false) } {
+            Ok(self.size)
+        } else {
+            Err(ExecError::OutOfMemory)
+        }
+    }
+
+    /// Write the arguments, environment and auxiliary vector onto the stack.
+    ///
+    /// Returns the initial stack pointer.
+    ///
+    /// # Safety
+    /// The address space must be valid and the stack must be mapped.
+    pub unsafe fn build(
+        &self,
+        space: usize,
+        exe: &Executable,
+        args: &[&str],
+        env: &[&str],
+    ) -> Result<usize, ExecError> {
+        // Strings and random bytes are placed at the very top
+        let mut strings = Vec::new();
+        let mut string_offsets = Vec::with_capacity(args.len() + env.len());
+
+        for string in args.iter().chain(env) {
+            string_offsets.push(strings.len());
+            strings.extend_from_slice(string.as_bytes());
+            strings.push(0);
+        }
+
+        let random_offset = strings.len();
+        strings.extend_from_slice(&api::seed(true).to_ne_bytes());
+        strings.extend_from_slice(&api::seed(true).to_ne_bytes());
+
+        let strings_addr = (self.top - strings.len()) & !0xF;
+
+        let auxv = [
+            (auxv::AT_PHDR, exe.phdr() as u64),
+            (auxv::AT_PHENT, exe.phent() as u64),
+            (auxv::AT_PHNUM, exe.phnum() as u64),
+            (auxv::AT_PAGESZ, PAGE_SIZE as u64),
+            (auxv::AT_BASE, 0),
+            (auxv::AT_ENTRY, exe.entry() as u64),
+            (auxv::AT_UID, 0),
+            (auxv::AT_EUID, 0),
+            (auxv::AT_GID, 0),
+            (auxv::AT_EGID, 0),
+            (auxv::AT_SECURE, 0),
+            (auxv::AT_RANDOM, (strings_addr + random_offset) as u64),
+            (auxv::AT_NULL, 0),
+        ];
+
+        // argc + argv + NULL + envp + NULL + auxv
+        let words = 1 + args.len() + 1 + env.len() + 1 + auxv.len() * 2;
+        let stack_pointer = (strings_addr - words * 8) & !0xF;
+
+        if self.top - stack_pointer > self.size {
+            return Err(ExecError::ArgumentsTooLong);
+        }
+
+        let mut pointers = Vec::with_capacity(words);
+        let mut offsets = string_offsets.iter();
+
+        pointers.push(args.len() as u64);
+        pointers.extend(
+            offsets
+                .by_ref()
+                .take(args.len())
+                .map(|offset| (strings_addr + offset) as u64),
+        );
+        pointers.push(0);
+        pointers.extend(offsets.map(|offset| (strings_addr + offset) as u64));
+        pointers.push(0);
+
+        for (key, value) in auxv {
+            pointers.push(key);
+            pointers.push(value);
+        }
+
+        let bytes = pointers
+            .iter()
+            .flat_map(|word| word.to_ne_bytes())
+            .collect::<Vec<u8>>();
+
+        unsafe {
+            if !api::process().write(space, stack_pointer, &bytes)
+                || !api::process().write(space, strings_addr, &strings)
+            {
+                return Err(ExecError::ArgumentsTooLong);
+            }
+        }
+
+        Ok(stack_pointer)
+    }
+}
@@ -0,0 +1,374 @@
+use crate::api;
+use crate::control::{self, CONTROL};
+use crate::ipc::error::IpcError;
+use crate::ipc::{self, Endpoint, MAX_MESSAGE_HANDLES, MAX_MESSAGE_SIZE, Message};
+use crate::process::Process;
+use crate::process::handle::{Handle, HandleTable};
+use alloc::string::String;
+use alloc::vec;
+use alloc::vec::Vec;
+use core::fmt::Write;
+
+/// The available system calls.
+///
+/// System calls are issued with `int 0x80`. The system call number is passed in `rax`
+/// and the arguments in `rdi`, `rsi`, `rdx`, `r10`, `r8` and `r9`.
+/// The result is returned in `rax`, where negative values are [SyscallError] codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u64)]
+pub enum Syscall {
+    /// Terminate the process with the exit code in the first argument.
+    Exit = 0,
+    /// Write a buffer to a file descriptor. Arguments: `fd`, `buf`, `len`.
+    ///
+    /// Only `1` (stdout) and `2` (stderr) are supported, both write to the control.
+    /// Returns the number of bytes written.
+    Write = 1,
+    /// Create a new IPC channel. Arguments: `handles`.
+    ///
+    /// Writes the handles of both endpoints to the `[u64; 2]` array at `handles`.
+    ChannelCreate = 2,
+    /// Send a message through a channel. Arguments: `handle`, `buf`, `len`, `handles`, `count`.
+    ///
+    /// The `count` handles in the `u64` array at `handles` are moved to the receiver.
+    /// They are closed even if sending fails.
+    ChannelSend = 3,
+    /// Receive a message from a channel.
+    /// Arguments: `handle`, `buf`, `len`, `handles`, `count`, `flags`.
+    ///
+    /// Blocks until a message arrives, unless `flags` contains [RECV_NONBLOCK].
+    /// Returns the number of data bytes, the number of received handles is returned in `rdx`.
+    /// The message stays queued if it does not fit into or can not be written to the buffers.
+    ChannelRecv = 4,
+    /// Close a handle. Arguments: `handle`.
+    HandleClose = 5,
+    /// Publish a named service. Arguments: `name`, `len`.
+    ///
+    /// Returns the handle of the listener endpoint, see [ipc::publish].
+    ServicePublish = 6,
+    /// Connect to a named service. Arguments: `name`, `len`.
+    ///
+    /// Returns the handle of the client endpoint, see [ipc::connect].
+    ServiceConnect = 7,
+}
+
+impl Syscall {
+    /// Returns the system call with the given number or [None] if it does not exist.
+    pub fn from_number(number: u64) -> Option<Self> {
+        match number {
+            0 => Some(Self::Exit),
+            1 => Some(Self::Write),
+            2 => Some(Self::ChannelCreate),
+            3 => Some(Self::ChannelSend),
+            4 => Some(Self::ChannelRecv),
+            5 => Some(Self::HandleClose),
+            6 => Some(Self::ServicePublish),
+            7 => Some(Self::ServiceConnect),
+            _ => None,
+        }
+    }
+}
+
+/// Flag for [Syscall::ChannelRecv] to return [SyscallError::WouldBlock] instead of blocking.
+pub const RECV_NONBLOCK: u64 = 1;
+
+/// Error codes returned by system calls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(i64)]
+pub enum SyscallError {
+    /// The system call number does not exist.
+    InvalidSyscall = -1,
+    /// An argument was invalid.
+    InvalidArgument = -2,
+    /// A pointer argument does not point to mapped user memory.
+    InvalidAddress = -3,
+    /// The operation would block.
+    WouldBlock = -4,
+    /// The other endpoint of the channel was closed.
+    PeerClosed = -5,
+    /// The handle does not exist.
+    InvalidHandle = -6,
+    /// The handle table of the process is full.
+    TooManyHandles = -7,
+    /// The message queue of the receiving endpoint is full.
+    QueueFull = -8,
+    /// The message is too large.
+    MessageTooLarge = -9,
+    /// The next message does not fit into the receive buffers.
+    BufferTooSmall = -10,
+    /// The requested service does not exist.
+    NotFound = -11,
+    /// The service already exists.
+    AlreadyExists = -12,
+}
+
+impl From<IpcError> for SyscallError {
+    fn from(err: IpcError) -> Self {
+        match err {
+            IpcError::WouldBlock => SyscallError::WouldBlock,
+            IpcError::PeerClosed => SyscallError::PeerClosed,
+            IpcError::QueueFull => SyscallError::QueueFull,
+            IpcError::MessageTooLarge => SyscallError::MessageTooLarge,
+            IpcError::BufferTooSmall => SyscallError::BufferTooSmall,
+            IpcError::ServiceNotFound => SyscallError::NotFound,
+            IpcError::ServiceAlreadyExists => SyscallError::AlreadyExists,
+        }
+    }
+}
+
+/// What to do with the process after a system call was handled.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SyscallAction {
+    /// Continue executing the process.
+    Continue,
+    /// The system call can not complete yet.
+    ///
+    /// The process is suspended and retries the system call once it is scheduled again.
+    Block,
+    /// Terminate the process with the given exit code.
+    Exit(i64),
+}
+
+/// The maximum number of bytes written with a single [Syscall::Write].
+const MAX_WRITE: usize = 4096;
+
+/// The maximum length of a service name in bytes.
+const MAX_SERVICE_NAME: usize = 64;
+
+/// The size of the `int 0x80` instruction, used to retry blocked system calls.
+const SYSCALL_INSTRUCTION_SIZE: u64 = 2;
+
+/// Handle the system call the given process trapped with.
+///
+/// The result is written into `rax` of the process context.
+pub fn handle(process: &mut Process) -> SyscallAction {
+    let context = &process.context;
+    let args = [
+        context.rdi,
+        context.rsi,
+        context.rdx,
+        context.r10,
+        context.r8,
+        context.r9,
+    ];
+
+    let result = match Syscall::from_number(context.rax) {
+        Some(Syscall::Exit) => return SyscallAction::Exit(args[0] as i64),
+        Some(Syscall::Write) => write(process, args[0], args[1] as usize, args[2] as usize),
+        Some(Syscall::ChannelCreate) => channel_create(process, args[0] as usize),
+        Some(Syscall::ChannelSend) => channel_send(process, &args),
+        Some(Syscall::ChannelRecv) => match channel_recv(process, &args) {
+            Err(SyscallError::WouldBlock) if args[5] & RECV_NONBLOCK == 0 => {
+                process.context.rip -= SYSCALL_INSTRUCTION_SIZE;
+
+                return SyscallAction::Block;
+            }
+            result => result,
+        },
+        Some(Syscall::HandleClose) => process
+            .handles
+            .remove(args[0])
+            .map(|_| 0)
+            .ok_or(SyscallError::InvalidHandle),
+        Some(Syscall::ServicePublish) => read_name(process, args[0] as usize, args[1] as usize)
+            .and_then(|name| insert_handle(process, ipc::publish(&name)?)),
+        Some(Syscall::ServiceConnect) => read_name(process, args[0] as usize, args[1] as usize)
+            .and_then(|name| insert_handle(process, ipc::connect(&name)?)),
+        None => Err(SyscallError::InvalidSyscall),
+    };
+
+    process.context.rax = match result {
+        Ok(value) => value,
+        Err(err) => err as i64 as u64,
+    };
+
+    SyscallAction::Continue
+}
+
+fn write(process: &Process, fd: u64, addr: usize, len: usize) -> Result<u64, SyscallError> {
+    if fd != 1 && fd != 2 {
+        return Err(SyscallError::InvalidArgument);
+    }
+
+    let mut buf = vec![0; len.min(MAX_WRITE)];
+
+    if !unsafe { api::process().read(process.space, addr, &mut buf) } {
+        return Err(SyscallError::InvalidAddress);
+    }
+
+    let text = String::from_utf8_lossy(&buf);
+
+    if control::is_init() {
+        CONTROL
+            .get()
+            .run(|ctrl| ctrl.write_str(&text))
+            .map_err(|_| SyscallError::InvalidArgument)?;
+    }
+
+    Ok(buf.len() as u64)
+}
+
+fn channel_create(process: &mut Process, addr: usize) -> Result<u64, SyscallError> {
+    let (first, second) = Endpoint::pair();
+
+    let first = insert_handle(process, first)?;
+    let second = insert_handle(process, second).inspect_err(|_| {
+        process.handles.remove(first);
+    })?;
+
+    let bytes = [first.to_ne_bytes(), second.to_ne_bytes()].concat();
+
+    if !unsafe { api::process().write(process.space, addr, &bytes) } {
+        process.handles.remove(first);
+        process.handles.remove(second);
+
+        return Err(SyscallError::InvalidAddress);
+    }
+
+    Ok(0)
+}
+
+fn channel_send(process: &mut Process, args: &[u64; 6]) -> Result<u64, SyscallError> {
+    let [handle, addr, len, handles_addr, count, _] = *args;
+    let (len, count) = (len as usize, count as usize);
+
+    if len > MAX_MESSAGE_SIZE || count > MAX_MESSAGE_HANDLES {
+        return Err(SyscallError::MessageTooLarge);
+    }
+
+    if process.handles.get(handle).is_none() {
+        return Err(SyscallError::InvalidHandle);
+    }
+
+    let mut data = vec![0; len];
+    let handles = read_handles(process, handles_addr as usize, count)?;
+
+    if !unsafe { api::process().read(process.space, addr as usize, &mut data) } {
+        return Err(SyscallError::InvalidAddress);
+    }
+
+    // An endpoint can not be sent through itself and every handle can only be moved once
+    if handles
+        .iter()
+        .enumerate()
+        .any(|(i, moved)| *moved == handle || handles[..i].contains(moved))
+    {
+        return Err(SyscallError::InvalidArgument);
+    }
+
+    if handles
+        .iter()
+        .any(|moved| process.handles.get(*moved).is_none())
+    {
+        return Err(SyscallError::InvalidHandle);
+    }
+
+    let endpoints = handles
+        .iter()
+        .filter_map(|moved| process.handles.remove(*moved))
+        .collect();
+
+    process
+        .handles
+        .get(handle)
+        .ok_or(SyscallError::InvalidHandle)?
+        .send(Message::new(data, endpoints))?;
+
+    Ok(0)
+}
+
+fn channel_recv(process: &mut Process, args: &[u64; 6]) -> Result<u64, SyscallError> {
+    let [handle, addr, len, handles_addr, count, _] = *args;
+
+    // Free slots are required up front, since the handles are inserted before they are written
+    let capacity = (count as usize)
+        .min(MAX_MESSAGE_HANDLES)
+        .min(HandleTable::MAX_HANDLES.saturating_sub(process.handles.len()));
+
+    let message = process
+        .handles
+        .get(handle)
+        .ok_or(SyscallError::InvalidHandle)?
+        .try_recv_within(len as usize, capacity)?;
+
+    deliver(process, message, addr as usize, handles_addr as usize).map_err(|message| {
+        // Put back, so the message is not lost on invalid receive buffers
+        if let Some(endpoint) = process.handles.get(handle) {
+            endpoint.requeue(message);
+        }
+
+        SyscallError::InvalidAddress
+    })
+}
+
+/// Write the given message to the receive buffers of the process and return its data length.
+///
+/// Returns the message again if a buffer is invalid.
+fn deliver(
+    process: &mut Process,
+    message: Message,
+    addr: usize,
+    handles_addr: usize,
+) -> Result<u64, Message> {
+    if !unsafe { api::process().write(process.space, addr, &message.data) } {
+        return Err(message);
+    }
+
+    let handles = message
+        .handles
+        .into_iter()
+        .filter_map(|endpoint| process.handles.insert(endpoint))
+        .collect::<Vec<_>>();
+
+    let bytes = handles
+        .iter()
+        .flat_map(|handle| handle.to_ne_bytes())
+        .collect::<Vec<u8>>();
+
+    if !unsafe { api::process().write(process.space, handles_addr, &bytes) } {
+        let endpoints = handles
+            .into_iter()
+            .filter_map(|handle| process.handles.remove(handle))
+            .collect();
+
+        return Err(Message::new(message.data, endpoints));
+    }
+
+    process.context.rdx = handles.len() as u64;
+
+    Ok(message.data.len() as u64)
+}
+
+fn insert_handle(process: &mut Process, endpoint: Endpoint) -> Result<Handle, SyscallError> {
+    process
+        .handles
+        .insert(endpoint)
+        .ok_or(SyscallError::TooManyHandles)
+}
+
+fn read_handles(process: &Process, addr: usize, count: usize) -> Result<Vec<Handle>, SyscallError> {
+    let mut bytes = vec![0; count * size_of::<Handle>()];
+
+    if !unsafe { api::process().read(process.space, addr, &mut bytes) } {
+        return Err(SyscallError::InvalidAddress);
+    }
+
+    Ok(bytes
+        .chunks_exact(size_of::<Handle>())
+        .map(|chunk| Handle::from_ne_bytes(chunk.try_into().unwrap()))
+        .collect())
+}
+
+fn read_name(process: &Process, addr: usize, len: usize) -> Result<String, SyscallError> {
+    if len == 0 || len > MAX_SERVICE_NAME {
+        return Err(SyscallError::InvalidArgument);
+    }
+
+    let mut bytes = vec![0; len];
+
+    if !unsafe { api::process().read(process.space, addr, &mut bytes) } {
+        return Err(SyscallError::InvalidAddress);
+    }
+
+    String::from_utf8(bytes).map_err(|_| SyscallError::InvalidArgument)
+}
@@ -0,0 +1,261 @@
+use crate::process::{Process, Slice};
+use crate::sync::mutex::Mutex;
+use alloc::collections::BTreeMap;
+use alloc::string::{String, ToString};
+use core::fmt::{Display, Formatter};
+
+/// Global [ProcessTable] instance.
+///
+/// Never log or lock the control while holding this lock, since the control locks it as well.
+pub static PROCESSES: Mutex<ProcessTable> = Mutex::new(ProcessTable::new());
+
+/// A process identifier.
+pub type Pid = usize;
+
+/// The state of a process in the [ProcessTable].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessState {
+    /// The process is waiting to be scheduled.
+    Ready,
+    /// The process is currently executing.
+    Running,
+    /// The process waits for a blocking system call and retries it whenever it is scheduled.
+    Blocked,
+    /// The process exited with the given exit code and waits to be reaped.
+    Exited(i64),
+    /// The process was killed and waits to be reaped.
+    Killed,
+}
+
+impl ProcessState {
+    /// Returns if the process can be scheduled.
+    pub fn is_runnable(&self) -> bool {
+        matches!(self, Self::Ready | Self::Blocked)
+    }
+
+    /// Returns if the process has terminated, either by exiting or by being killed.
+    pub fn is_terminated(&self) -> bool {
+        matches!(self, Self::Exited(_) | Self::Killed)
+    }
+}
+
+impl Display for ProcessState {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Ready => write!(f, "ready"),
+            Self::Running => write!(f, "running"),
+            Self::Blocked => write!(f, "blocked"),
+            Self::Exited(code) => write!(f, "exited({code})"),
+            Self::Killed => write!(f, "killed"),
+        }
+    }
+}
+
+/// An entry of the [ProcessTable].
+pub struct ProcessEntry {
+    pid: Pid,
+    name: String,
+    state: ProcessState,
+    ticks: u64,
+    memory: usize,
+    process: Option<Process>,
+}
+
+impl ProcessEntry {
+    /// Returns the identifier of the process.
+    pub fn pid(&self) -> Pid {
+        self.pid
+    }
+
+    /// Returns the name of the process.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Returns the state of the process.
+    pub fn state(&self) -> ProcessState {
+        self.state
+    }
+
+    /// Returns the number of timer ticks the process has been executing for.
+    pub fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// Returns the number of bytes mapped by the process. Terminated processes use no memory.
+    pub fn memory(&self) -> usize {
+        self.memory
+    }
+}
+
+/// The table of all processes, including terminated ones that were not reaped yet.
+///
+/// A process is taken out of the table while it executes and put back afterward,
+/// so the table is never locked while user code runs.
+pub struct ProcessTable {
+    entries: BTreeMap<Pid, ProcessEntry>,
+    next_pid: Pid,
+    foreground: Option<Pid>,
+}
+
+impl ProcessTable {
+    /// Create a new, empty process table.
+    pub const fn new() -> Self {
+        Self {
+            entries: BTreeMap::new(),
+            next_pid: 1,
+            foreground: None,
+        }
+    }
+
+    /// Insert a new process in the [ProcessState::Ready] state and return its [Pid].
+    pub fn insert(&mut self, process: Process) -> Pid {
+        let pid = self.next_pid;
+        self.next_pid += 1;
+
+        self.entries.insert(
+            pid,
+            ProcessEntry {
+                pid,
+                name: process.name().to_string(),
+                state: ProcessState::Ready,
+                ticks: 0,
+                memory: process.memory(),
+                process: Some(process),
+            },
+        );
+
+        pid
+    }
+
+    /// Returns the entry of the given process.
+    pub fn get(&self, pid: Pid) -> Option<&ProcessEntry> {
+        self.entries.get(&pid)
+    }
+
+    /// Returns an iterator over all entries, ordered by [Pid].
+    pub fn iter(&self) -> impl Iterator<Item = &ProcessEntry> {
+        self.entries.values()
+    }
+
+    /// Returns the identifiers of all processes that can be scheduled.
+    pub fn ready(&self) -> impl Iterator<Item = Pid> + '_ {
+        self.entries
+            .values()
+            .filter(|entry| entry.state.is_runnable())
+            .map(|entry| entry.pid)
+    }
+
+    /// Take a runnable process out of the table to execute it and mark it as running.
+    pub fn take(&mut self, pid: Pid) -> Option<Process> {
+        let entry = self
+            .entries
+            .get_mut(&pid)
+            .filter(|entry| entry.state.is_runnable())?;
+
+        entry.state = ProcessState::Running;
+        entry.process.take()
+    }
+
+    /// Put a process back after it executed a [Slice] of the given number of ticks.
+    ///
+    /// Returns the new state, or [None] if the process was killed in the meantime.
+    pub fn put_back(
+        &mut self,
+        pid: Pid,
+        process: Process,
+        ticks: u64,
+        slice: Slice,
+    ) -> Option<ProcessState> {
+        let entry = self.entries.get_mut(&pid)?;
+
+        entry.ticks += ticks;
+
+        if entry.state != ProcessState::Running {
+            return None;
+        }
+
+        entry.state = match slice {
+            Slice::Preempted => ProcessState::Ready,
+            Slice::Blocked => ProcessState::Blocked,
+            Slice::Exited(code) => ProcessState::Exited(code),
+        };
+
+        let state = entry.state;
+
+        if state.is_terminated() {
+            entry.memory = 0;
+            self.reap_foreground(pid);
+        } else {
+            entry.process = Some(process);
+        }
+
+        Some(state)
+    }
+
+    /// Kill the given process, freeing all of its memory.
+    ///
+    /// Returns `false` if the process does not exist or already terminated.
+    pub fn kill(&mut self, pid: Pid) -> bool {
+        let Some(entry) = self
+            .entries
+            .get_mut(&pid)
+            .filter(|entry| !entry.state.is_terminated())
+        else {
+            return false;
+        };
+
+        entry.state = ProcessState::Killed;
+        entry.memory = 0;
+        entry.process = None;
+
+        self.reap_foreground(pid);
+
+        true
+    }
+
+    /// Remove a terminated process from the table and return its final state.
+    ///
+    /// Returns [None] if the process does not exist or did not terminate yet.
+    pub fn reap(&mut self, pid: Pid) -> Option<ProcessState> {
+        let state = self.entries.get(&pid)?.state;
+
+        if !state.is_terminated() {
+            return None;
+        }
+
+        self.entries.remove(&pid);
+
+        Some(state)
+    }
+
+    /// Returns the foreground job, which blocks the control until it terminates.
+    pub fn foreground(&self) -> Option<Pid> {
+        self.foreground
+    }
+
+    /// Make the given process the foreground job.
+    ///
+    /// Returns `false` if the process does not exist or already terminated.
+    pub fn set_foreground(&mut self, pid: Pid) -> bool {
+        if self
+            .entries
+            .get(&pid)
+            .is_none_or(|entry| entry.state.is_terminated())
+        {
+            return false;
+        }
+
+        self.foreground = Some(pid);
+
+        true
+    }
+
+    /// The foreground job is reaped as soon as it terminates, since the control waits for it.
+    fn reap_foreground(&mut self, pid: Pid) {
+        if self.foreground == Some(pid) {
+            self.foreground = None;
+            self.entries.remove(&pid);
+        }
+    }
+}
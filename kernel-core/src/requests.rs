@@ -1,10 +1,11 @@
 use limine::BaseRevision;
+use limine::file::File;
 use limine::mp::RequestFlags;
 use limine::paging::Mode;
 use limine::request::{
-    BootloaderInfoRequest, DateAtBootRequest, FramebufferRequest, HhdmRequest, MemoryMapRequest,
-    ModuleRequest, MpRequest, PagingModeRequest, RequestsEndMarker, RequestsStartMarker,
-    RsdpRequest,
+    BootloaderInfoRequest, DateAtBootRequest, ExecutableCmdlineRequest, FramebufferRequest,
+    HhdmRequest, MemoryMapRequest, ModuleRequest, MpRequest, PagingModeRequest, RequestsEndMarker,
+    RequestsStartMarker, RsdpRequest,
 };
 use limine::response::{
     BootloaderInfoResponse, DateAtBootResponse, FramebufferResponse, HhdmResponse,
@@ -67,6 +68,11 @@ static MP_REQUEST: MpRequest = MpRequest::with_revision(0).with_flags(RequestFla
 #[unsafe(link_section = ".requests")]
 static MODULE_REQUEST: ModuleRequest = ModuleRequest::with_revision(1);
 
+/// Request the kernel command line from limine.
+#[used]
+#[unsafe(link_section = ".requests")]
+static CMDLINE_REQUEST: ExecutableCmdlineRequest = ExecutableCmdlineRequest::with_revision(0);
+
 /// The end marker for Limine requests.
 #[used]
 #[unsafe(link_section = ".requests_end_marker")]
@@ -147,7 +153,29 @@ pub fn multi_processors<'a>() -> &'a MpResponse {
         .expect("Failed to get mp response")
 }
 
+/// Returns the kernel command line or an empty string if there is none or it is not valid UTF-8.
+pub fn cmdline<'a>() -> &'a str {
+    CMDLINE_REQUEST
+        .get_response()
+        .and_then(|response| response.cmdline().to_str().ok())
+        .unwrap_or_default()
+}
+
 /// Returns the [ModuleRequest] or [None] if no modules were found.
 pub fn modules<'a>() -> Option<&'a ModuleResponse> {
     MODULE_REQUEST.get_response()
 }
+
+/// Returns the limine module with the given path or [None] if it was not found.
+///
+/// The path may either be complete (e.g. `/boot/hello`) or omit leading directories (e.g. `hello`).
+pub fn module_file<'a>(path: &str) -> Option<&'a File> {
+    modules()?.modules().iter().copied().find(|file| {
+        let file_path = file.path().to_str().unwrap_or_default();
+
+        file_path == path
+            || file_path
+                .strip_suffix(path)
+                .is_some_and(|rest| rest.ends_with('/'))
+    })
+}
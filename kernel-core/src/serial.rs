@@ -2,7 +2,17 @@ use crate::api;
 use core::fmt;
 use core::fmt::Write;
 
-const PORT: u16 = 0x3F8;
+/// The I/O port of the first serial port (COM1).
+pub const PORT: u16 = 0x3F8;
+
+/// The interrupt enable register, relative to [PORT].
+const INTERRUPT_ENABLE: u16 = 1;
+/// The FIFO control register, relative to [PORT].
+const FIFO_CONTROL: u16 = 2;
+/// The modem control register, relative to [PORT].
+const MODEM_CONTROL: u16 = 4;
+/// The line status register, relative to [PORT].
+const LINE_STATUS: u16 = 5;
 
 /// Prints a string to the serial console port.
 #[macro_export]
@@ -28,6 +38,34 @@ pub fn print(args: fmt::Arguments) {
         .expect("Printing to serial failed");
 }
 
+/// Enable the interrupt of the serial console port for received data.
+///
+/// The interrupt must be routed to a handler, which reads the data with [read_byte].
+///
+/// # Safety
+/// This must only be called once the interrupt handler is installed.
+pub unsafe fn enable_receive_interrupt() {
+    unsafe {
+        // Disable interrupts while configuring
+        api::port().write_u8(PORT + INTERRUPT_ENABLE, 0x00);
+        // Enable and clear the FIFOs with a 1 byte trigger level for low latency typing
+        api::port().write_u8(PORT + FIFO_CONTROL, 0x07);
+        // Set DTR, RTS and OUT2, which connects the interrupt line
+        api::port().write_u8(PORT + MODEM_CONTROL, 0x0B);
+        // Interrupt once data is available
+        api::port().write_u8(PORT + INTERRUPT_ENABLE, 0x01);
+    }
+}
+
+/// Read a received byte from the serial console port, if there is one.
+pub fn read_byte() -> Option<u8> {
+    unsafe {
+        let ready = api::port().read_u8(PORT + LINE_STATUS) & 0x01 != 0;
+
+        ready.then(|| api::port().read_u8(PORT))
+    }
+}
+
 /// A writer that writes everything to the serial console port.
 pub struct SerialWriter;
 
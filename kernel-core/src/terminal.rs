@@ -1,4 +1,5 @@
 use crate::control::InnerControl;
+use crate::control::scrollback::{self, Scrollback};
 use alloc::vec::Vec;
 use ratatui::buffer::Buffer;
 use ratatui::layout::Rect;
@@ -8,30 +9,44 @@ use ustyle::{Attributes, Color, Style};
 
 /// A terminal-style text box widget.
 pub struct TerminalBox<'a> {
-    buf: &'a [Vec<(char, Style)>],
+    buf: &'a Scrollback,
     command: &'a str,
+    cursor: usize,
     default: Style,
     scroll_offset: usize,
+    search: Option<(&'a [char], Option<usize>)>,
 }
 
 impl<'a> TerminalBox<'a> {
-    /// Create a new [TerminalBox] from a buffer, command line and default style.
+    /// Create a new [TerminalBox] from a buffer, command line with its cursor and default style.
+    ///
+    /// The `cursor` is the char position in the command line.
     pub fn new(
-        buf: &'a [Vec<(char, Style)>],
+        buf: &'a Scrollback,
         command: &'a str,
+        cursor: usize,
         default: Style,
         scroll_offset: usize,
     ) -> Self {
         Self {
             buf,
             command,
+            cursor,
             default,
             scroll_offset,
+            search: None,
         }
     }
 
+    /// Highlight the occurrences of the search pattern,
+    /// with a different color inside the line of the current match.
+    pub fn search(mut self, pattern: &'a [char], current: Option<usize>) -> Self {
+        self.search = Some((pattern, current));
+        self
+    }
+
     #[inline]
-    fn style_to_tui(style: &Style, default: &Style) -> TuiStyle {
+    pub(crate) fn style_to_tui(style: &Style, default: &Style) -> TuiStyle {
         let fg = style.foreground.to_rgb().unwrap_or(
             default
                 .foreground
@@ -51,25 +66,31 @@ impl<'a> TerminalBox<'a> {
             .bg(TuiColor::from(bg))
     }
 
-    fn build_command_line(command: &str, default: Style) -> Vec<(char, Style)> {
+    fn build_command_line(command: &str, cursor: usize, default: Style) -> Vec<(char, Style)> {
         // TODO: Don't create extra Vec for command line
         let mut buf = Vec::with_capacity(command.len() + 3);
 
         buf.push((InnerControl::COMMAND_PREFIX, default));
         buf.push((' ', default));
 
-        for ch in command.chars() {
-            buf.push((ch, default));
+        // The char under the cursor is inverted
+        let inverted = Style::new(Color::DarkerGray, Color::BrighterGray, Attributes::empty());
+
+        for (i, ch) in command.chars().enumerate() {
+            buf.push((ch, if i == cursor { inverted } else { default }));
         }
 
-        buf.push((
-            InnerControl::COMMAND_SUFFIX,
-            Style::new(
-                Color::BrighterGray,
-                Color::BrighterGray,
-                Attributes::empty(),
-            ),
-        ));
+        // The suffix is the cursor at the end of the line
+        if cursor >= buf.len() - 2 {
+            buf.push((
+                InnerControl::COMMAND_SUFFIX,
+                Style::new(
+                    Color::BrighterGray,
+                    Color::BrighterGray,
+                    Attributes::empty(),
+                ),
+            ));
+        }
 
         buf
     }
@@ -79,6 +100,9 @@ impl<'a> Widget for TerminalBox<'a> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         buf.reset();
 
+        let match_style = Style::new(Color::DarkerGray, Color::BrightYellow, Attributes::empty());
+        let current_style = Style::new(Color::DarkerGray, Color::BrightGreen, Attributes::empty());
+
         let max_lines = area.height as usize;
         let total_lines = self.buf.len() + 1; // + command line
 
@@ -99,19 +123,41 @@ impl<'a> Widget for TerminalBox<'a> {
 
             // Normal buffer line
             let cmd_storage;
-            if line_idx < self.buf.len() {
-                line = &self.buf[line_idx];
+            if let Some(buf_line) = self.buf.get(line_idx) {
+                line = buf_line;
             } else {
                 // Command line (built on the fly, no cloning of scrollback)
-                cmd_storage = Self::build_command_line(self.command, self.default);
+                cmd_storage = Self::build_command_line(self.command, self.cursor, self.default);
                 line = &cmd_storage;
             }
 
-            for (ch, style) in line {
+            // The chars of the search pattern occurrences are highlighted
+            let mut highlighted = Vec::new();
+
+            if let Some((pattern, current)) = self.search
+                && line_idx < self.buf.len()
+            {
+                let style = if current == Some(line_idx) {
+                    current_style
+                } else {
+                    match_style
+                };
+
+                highlighted = scrollback::matches(line, pattern)
+                    .map(|start| (start..start + pattern.len(), style))
+                    .collect();
+            }
+
+            for (idx, (ch, style)) in line.iter().enumerate() {
                 if x >= area.right() {
                     break;
                 }
 
+                let style = highlighted
+                    .iter()
+                    .find(|(range, _)| range.contains(&idx))
+                    .map_or(style, |(_, style)| style);
+
                 let cell = buf.cell_mut((x, y)).unwrap();
                 cell.set_char(*ch);
                 cell.set_style(Self::style_to_tui(style, &self.default));
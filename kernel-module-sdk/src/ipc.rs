@@ -0,0 +1,48 @@
+use kernel_core::ipc::error::IpcError;
+use kernel_core::ipc::{Endpoint, Message};
+
+unsafe extern "Rust" {
+    fn kernel_ipc_channel() -> (Endpoint, Endpoint);
+    fn kernel_ipc_send(endpoint: &Endpoint, message: Message) -> Result<(), IpcError>;
+    fn kernel_ipc_try_recv(endpoint: &Endpoint) -> Result<Message, IpcError>;
+    fn kernel_ipc_recv(endpoint: &Endpoint) -> Result<Message, IpcError>;
+    fn kernel_ipc_publish(name: &str) -> Result<Endpoint, IpcError>;
+    fn kernel_ipc_connect(name: &str) -> Result<Endpoint, IpcError>;
+}
+
+/// Create a new channel and return both of its endpoints.
+pub fn channel() -> (Endpoint, Endpoint) {
+    unsafe { kernel_ipc_channel() }
+}
+
+/// Send a message to the peer of the given endpoint.
+///
+/// The message, including its handles, is dropped if sending fails.
+pub fn send(endpoint: &Endpoint, message: Message) -> Result<(), IpcError> {
+    unsafe { kernel_ipc_send(endpoint, message) }
+}
+
+/// Receive the next message at the given endpoint without blocking.
+///
+/// Returns [IpcError::WouldBlock] if there is no message yet.
+pub fn try_recv(endpoint: &Endpoint) -> Result<Message, IpcError> {
+    unsafe { kernel_ipc_try_recv(endpoint) }
+}
+
+/// Receive the next message at the given endpoint,
+/// blocking until one arrives or the peer is closed.
+pub fn recv(endpoint: &Endpoint) -> Result<Message, IpcError> {
+    unsafe { kernel_ipc_recv(endpoint) }
+}
+
+/// Publish a service with the given name and return its listener endpoint.
+///
+/// The listener receives a message carrying the server side endpoint for every connection.
+pub fn publish(name: &str) -> Result<Endpoint, IpcError> {
+    unsafe { kernel_ipc_publish(name) }
+}
+
+/// Connect to the service with the given name and return the client side endpoint.
+pub fn connect(name: &str) -> Result<Endpoint, IpcError> {
+    unsafe { kernel_ipc_connect(name) }
+}
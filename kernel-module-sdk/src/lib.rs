@@ -0,0 +1,108 @@
+//! SDK for building kernel modules for Subatomic OS.
+//!
+//! Modules are `cdylib` crates built with the `target-x86_64-module.json` target and linked
+//! with the `linker-x86_64-module.ld` linker script of this crate. They declare themselves
+//! using the [declare_module] macro, which installs the [runtime] on initialization.
+//!
+//! Modules run inside the kernel, but link their own copy of `kernel-core`.
+//! Kernel state must therefore be accessed through the [ModuleContext]
+//! or the exported kernel symbols, never through the statics of `kernel-core`.
+//!
+//! A panic of a module can't be caught by the kernel, since modules don't unwind.
+//! The panic handler of the [runtime] logs the panic and halts the CPU forever,
+//! which hangs the whole system. Modules should therefore return errors from their hooks
+//! and commands instead of panicking.
+
+#![no_std]
+#![warn(missing_docs)]
+
+extern crate alloc;
+
+pub use kernel_core;
+pub use kernel_core::module::context::ModuleContext;
+pub use log;
+
+/// Contains the allocator, logger and panic handler of a module.
+///
+/// The panic handler halts the system, see the [crate] documentation.
+pub mod runtime;
+
+/// Contains the message-passing IPC of the kernel.
+///
+/// The channels and services live in the kernel, so modules must use these functions
+/// instead of the ones of `kernel-core`. Endpoints held by a module should be dropped
+/// in its `exit` hook, which closes them.
+pub mod ipc;
+
+/// Declare the `KERNEL_MODULE` descriptor of the module.
+///
+/// All hooks are plain Rust functions, which are wrapped into the `C` ABI hooks of the
+/// descriptor. The `init` hook additionally installs the [runtime] before it is called,
+/// so allocations, logging and the `kernel-core` API work afterward.
+/// The optional `dependencies` and `provides` lists name modules or services,
+/// the module is only initialized after all of its dependencies are running.
+///
+/// ```ignore
+/// kernel_module_sdk::declare_module! {
+///     name: "hello",
+///     version: env!("CARGO_PKG_VERSION"),
+///     author: "Subatomic OS",
+///     description: "Greets the control.",
+///     dependencies: ["greeter"],
+///     provides: ["hello-service"],
+///     init: init,
+///     update: update,
+///     exit: exit,
+/// }
+/// ```
+#[macro_export]
+macro_rules! declare_module {
+    (
+        name: $name:expr,
+        version: $version:expr,
+        author: $author:expr,
+        description: $description:expr,
+        $(dependencies: [$($dependency:expr),* $(,)?],)?
+        $(provides: [$($service:expr),* $(,)?],)?
+        init: $init:path,
+        update: $update:path,
+        exit: $exit:path $(,)?
+    ) => {
+        #[used]
+        #[unsafe(no_mangle)]
+        pub static KERNEL_MODULE: $crate::kernel_core::module::descriptor::ModuleDescriptor = {
+            use $crate::kernel_core::module::descriptor::{
+                ModuleDescriptor, ModuleStatus, ModuleStr,
+            };
+
+            const DEPENDENCIES: &[ModuleStr] = &[$($(ModuleStr::new($dependency)),*)?];
+            const PROVIDES: &[ModuleStr] = &[$($(ModuleStr::new($service)),*)?];
+
+            extern "C" fn __module_init(context: &mut $crate::ModuleContext) -> ModuleStatus {
+                unsafe { $crate::runtime::init() };
+
+                ModuleStatus::from($init(context))
+            }
+
+            extern "C" fn __module_update() {
+                $update()
+            }
+
+            extern "C" fn __module_exit() {
+                $exit()
+            }
+
+            ModuleDescriptor::new(
+                $name,
+                $version,
+                $author,
+                $description,
+                DEPENDENCIES,
+                PROVIDES,
+                __module_init,
+                __module_update,
+                __module_exit,
+            )
+        };
+    };
+}
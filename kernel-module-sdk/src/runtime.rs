@@ -0,0 +1,93 @@
+use alloc::string::String;
+use core::alloc::{GlobalAlloc, Layout};
+use core::fmt::Write;
+use kernel_core::api::{self, KernelApi};
+use log::{LevelFilter, Log, Metadata, Record};
+
+unsafe extern "C" {
+    fn kernel_log(level: u8, msg: *const u8, len: usize);
+    fn kernel_alloc(size: usize, align: usize) -> *mut u8;
+    fn kernel_alloc_zeroed(size: usize, align: usize) -> *mut u8;
+    fn kernel_dealloc(ptr: *mut u8, size: usize, align: usize);
+    fn kernel_realloc(ptr: *mut u8, size: usize, align: usize, new_size: usize) -> *mut u8;
+}
+
+unsafe extern "Rust" {
+    fn kernel_api() -> KernelApi;
+}
+
+/// Initialize the runtime of the module.
+///
+/// Installs the [KernelLogger] and sets the `kernel-core` API to the one of the kernel.
+///
+/// # Safety
+/// Must only be called once, before any logging or API usage.
+/// This is done by the `init` hook generated by [declare_module](crate::declare_module).
+#[doc(hidden)]
+pub unsafe fn init() {
+    unsafe {
+        log::set_max_level_racy(LevelFilter::Trace);
+        let _ = log::set_logger_racy(&KernelLogger);
+
+        api::set(kernel_api());
+    }
+}
+
+/// The global allocator of a module, which allocates on the kernel heap.
+#[global_allocator]
+pub static ALLOC: KernelAllocator = KernelAllocator;
+
+/// Allocates memory on the kernel heap using the exported kernel allocation functions.
+///
+/// Memory can therefore be freely passed between the kernel and modules.
+pub struct KernelAllocator;
+
+unsafe impl GlobalAlloc for KernelAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        unsafe { kernel_alloc(layout.size(), layout.align()) }
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        unsafe { kernel_dealloc(ptr, layout.size(), layout.align()) }
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        unsafe { kernel_alloc_zeroed(layout.size(), layout.align()) }
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        unsafe { kernel_realloc(ptr, layout.size(), layout.align(), new_size) }
+    }
+}
+
+/// Forwards the logs of a module to the kernel logger.
+pub struct KernelLogger;
+
+impl Log for KernelLogger {
+    fn enabled(&self, _metadata: &Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &Record) {
+        let mut msg = String::new();
+
+        if write!(msg, "{}", record.args()).is_ok() {
+            unsafe { kernel_log(record.level() as u8, msg.as_ptr(), msg.len()) }
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+/// Logs the panic and halts the CPU forever.
+///
+/// The kernel can't recover from a module panic, so this hangs the whole system.
+#[cfg(feature = "panic-handler")]
+#[panic_handler]
+fn panic(info: &core::panic::PanicInfo) -> ! {
+    log::error!("Kernel module panicked: {info}");
+
+    loop {
+        api::halt()
+    }
+}
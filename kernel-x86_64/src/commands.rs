@@ -1,18 +1,59 @@
 use crate::cpuid;
+use crate::interrupts::InterruptVector;
+use crate::interrupts::keyboard::{self, KeyLayout};
+use alloc::format;
 use alloc::string::String;
-use kernel_core::control::command::Command;
+use kernel_core::control::command::args::{Arg, ArgType, Args};
+use kernel_core::control::command::{Command, CommandIo};
 
-pub const COMMANDS: [Command; 1] = [Command {
-    name: "cpuid",
-    description: "Get CPUID information",
-    usage: "cpuid",
-    run: cpuid,
-}];
+pub const COMMANDS: [Command; 3] = [
+    Command::new("cpuid", "Get CPUID information", cpuid),
+    Command::new(
+        "interrupts",
+        "Get the number of handled interrupts per vector",
+        interrupts,
+    ),
+    Command::new("keyboard", "Set the keyboard layout", set_keyboard).args(&[Arg::positional(
+        "layout",
+        "The keyboard layout.",
+        ArgType::Choice(KeyLayout::NAMES),
+    )]),
+];
 
-fn cpuid(_: String) -> Result<(), String> {
+fn cpuid(_: &Args, io: &mut CommandIo) -> Result<(), String> {
     let cpuid = cpuid::cpuid();
 
-    log::info!("{cpuid:#?}");
+    writeln!(io, "{cpuid:#?}");
+
+    Ok(())
+}
+
+fn interrupts(_: &Args, io: &mut CommandIo) -> Result<(), String> {
+    writeln!(io, "{:<8}  {:<10}  {:>12}", "VECTOR", "NAME", "COUNT");
+
+    for vector in InterruptVector::COUNTED {
+        writeln!(
+            io,
+            "{:<8}  {:<10}  {:>12}",
+            vector.with_offset(),
+            // Derived debug output ignores the width
+            format!("{vector:?}"),
+            vector.counter()
+        );
+    }
+
+    Ok(())
+}
+
+fn set_keyboard(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    let name = args.str("layout").unwrap_or_default();
+
+    // The name was already validated against the layout names
+    let layout = KeyLayout::from_name(name).unwrap_or(KeyLayout::Us104Key);
+
+    keyboard::set_layout(layout);
+
+    writeln!(io, "Keyboard layout set to {layout:?}.");
 
     Ok(())
 }
@@ -1,7 +1,9 @@
 use crate::acpi::ACPI;
+use crate::interrupts::keyboard::{self, KeyLayout};
 use crate::interrupts::{apic, idt};
 use crate::memory::{allocator, frame_alloc, mapper};
 use crate::{cpuid, gdt, memory};
+use kernel_core::config::BOOT_CONFIG;
 use kernel_core::device::{DeviceHub, pci};
 use x86_64::PhysAddr;
 use x86_64::structures::paging::PageTableFlags;
@@ -23,6 +25,9 @@ pub unsafe fn init() {
         log::info!("Initializing Interrupt Descriptor Table...");
         idt::init();
 
+        log::info!("Initializing user mode support...");
+        crate::process::init();
+
         log::info!("Initializing physical memory offset...");
         memory::init_phys_mem();
 
@@ -47,7 +52,7 @@ pub unsafe fn setup() {
     unsafe {
         {
             log::info!("Registering architecture specific commands...");
-            let control = kernel_core::control::CONTROL.get_mut();
+            let control = kernel_core::control::CONTROL.get();
 
             for command in crate::commands::COMMANDS {
                 control.register(command);
@@ -60,6 +65,21 @@ pub unsafe fn setup() {
         log::info!("Initializing Advanced Programmable Interrupt Controller...");
         apic::init();
 
+        if let Some(name) = &BOOT_CONFIG.get().kbd {
+            log::info!("Setting keyboard layout to {name}...");
+
+            match KeyLayout::from_name(name) {
+                Some(layout) => keyboard::set_layout(layout),
+                None => log::warn!(
+                    "Unknown keyboard layout '{name}'. Available: {}.",
+                    KeyLayout::NAMES.join(", ")
+                ),
+            }
+        }
+
+        log::info!("Enabling serial input...");
+        kernel_core::serial::enable_receive_interrupt();
+
         log::info!("Initializing PCI Device Hub...");
         {
             let mcfg = ACPI.get().mcfg.get();
@@ -12,7 +12,7 @@ use x86_64::structures::paging::PageTableFlags;
 /// Milliseconds per APIC timer ticks.
 ///
 /// Set to 10 millis for low overhead but frequently enough interrupts.
-const MILLIS_PER_TICK: u32 = 10;
+pub const MILLIS_PER_TICK: u32 = 10;
 
 static IO_APIC: InitData<IoApic> = InitData::uninit();
 static LOCAL_APIC: InitData<LocalApicWrapper> = InitData::uninit();
@@ -81,6 +81,7 @@ unsafe fn init_io_apic(apic_addr: u64) {
         apic.init(INTERRUPT_OFFSET);
 
         apic.enable_irq(InterruptVector::Keyboard as u8);
+        apic.enable_irq(InterruptVector::Serial as u8);
 
         IO_APIC.init(apic)
     };
@@ -1,7 +1,29 @@
+use crate::process;
+use x86_64::PrivilegeLevel;
+use x86_64::registers::control::Cr2;
 use x86_64::structures::idt::{InterruptStackFrame, PageFaultErrorCode};
 
+/// Terminate the current user program at the faulting instruction,
+/// if the exception was caused in user mode. See [leave_if_user_at].
+fn leave_if_user(frame: &InterruptStackFrame, name: &'static str) {
+    leave_if_user_at(frame, name, frame.instruction_pointer.as_u64() as usize);
+}
+
+/// Terminate the current user program with the given exception name and faulting address,
+/// if the exception was caused in user mode.
+///
+/// Only returns for exceptions caused in kernel mode, which must be handled by the caller.
+/// Otherwise, this switches away from the faulting process and never returns.
+fn leave_if_user_at(frame: &InterruptStackFrame, name: &'static str, addr: usize) {
+    if frame.code_segment.rpl() == PrivilegeLevel::Ring3 {
+        unsafe { process::leave_exception(name, addr) }
+    }
+}
+
 #[cold]
 pub extern "x86-interrupt" fn x87_floating_point_handler(frame: InterruptStackFrame) {
+    leave_if_user(&frame, "x87 Floating Point Exception");
+
     log::error!("Encountered x87 Floating Point Exception");
     log::error!("x87 Floating Point Exception: {:#?}", frame);
 }
@@ -27,6 +49,8 @@ pub extern "x86-interrupt" fn virtualization_exception_handler(frame: InterruptS
 
 #[cold]
 pub extern "x86-interrupt" fn stack_segment_fault_handler(frame: InterruptStackFrame, code: u64) {
+    leave_if_user(&frame, "Stack Segment Fault");
+
     log::error!("Encountered Stack Segment Fault Exception");
     log::error!(
         "Stack Segment Fault Exception with code {}: {:#?}",
@@ -37,6 +61,8 @@ pub extern "x86-interrupt" fn stack_segment_fault_handler(frame: InterruptStackF
 
 #[cold]
 pub extern "x86-interrupt" fn simd_floating_point_handler(frame: InterruptStackFrame) {
+    leave_if_user(&frame, "SIMD Floating Point Exception");
+
     log::error!("Encountered SIMD Floating Point Exception");
     log::error!("SIMD Floating Point Exception: {:#?}", frame);
 }
@@ -62,6 +88,8 @@ pub extern "x86-interrupt" fn page_fault_handler(
     frame: InterruptStackFrame,
     code: PageFaultErrorCode,
 ) {
+    leave_if_user_at(&frame, "Page Fault", Cr2::read_raw() as usize);
+
     log::error!("Encountered Page Fault Exception");
     panic!("Page Fault Exception with code {:?}: {:#?}", code, frame);
 }
@@ -86,6 +114,8 @@ pub extern "x86-interrupt" fn invalid_tss_handler(frame: InterruptStackFrame, co
 
 #[cold]
 pub extern "x86-interrupt" fn invalid_opcode_handler(frame: InterruptStackFrame) {
+    leave_if_user(&frame, "Invalid Opcode");
+
     log::error!("Encountered Invalid Opcode Exception");
     log::error!("Invalid Opcode Exception: {:#?}", frame);
 }
@@ -101,6 +131,8 @@ pub extern "x86-interrupt" fn general_protection_fault_handler(
     frame: InterruptStackFrame,
     code: u64,
 ) {
+    leave_if_user(&frame, "General Protection Fault");
+
     log::error!("Encountered General Protection Fault Exception");
     log::error!(
         "General Protection Fault Exception with code {}: {:#?}",
@@ -117,12 +149,16 @@ pub extern "x86-interrupt" fn double_fault_handler(frame: InterruptStackFrame, c
 
 #[cold]
 pub extern "x86-interrupt" fn divide_error_handler(frame: InterruptStackFrame) {
+    leave_if_user(&frame, "Divide Error");
+
     log::error!("Encountered Divide Error Exception");
     log::error!("Divide Error Exception: {:#?}", frame);
 }
 
 #[cold]
 pub extern "x86-interrupt" fn device_not_available_handler(frame: InterruptStackFrame) {
+    leave_if_user(&frame, "Device Not Available Exception");
+
     log::error!("Encountered Device Not Available Exception");
     log::error!("Device Not Available Exception: {:#?}", frame);
 }
@@ -138,24 +174,32 @@ pub extern "x86-interrupt" fn cp_protection_exception_handler(
 
 #[cold]
 pub extern "x86-interrupt" fn breakpoint_handler(frame: InterruptStackFrame) {
+    leave_if_user(&frame, "Breakpoint");
+
     log::error!("Encountered Breakpoint Exception");
     log::error!("Breakpoint Exception: {:#?}", frame);
 }
 
 #[cold]
 pub extern "x86-interrupt" fn alignment_check_handler(frame: InterruptStackFrame, code: u64) {
+    leave_if_user(&frame, "Alignment Check");
+
     log::error!("Encountered Alignment Check Exception");
     log::error!("Alignment Check Exception with code {}: {:#?}", code, frame);
 }
 
 #[cold]
 pub extern "x86-interrupt" fn bound_range_exceeded_handler(frame: InterruptStackFrame) {
+    leave_if_user(&frame, "Bound Range Exceeded Exception");
+
     log::error!("Encountered Bound Range Exceeded Exception");
     log::error!("Bound Range Exceeded Exception: {:#?}", frame);
 }
 
 #[cold]
 pub extern "x86-interrupt" fn overflow_handler(frame: InterruptStackFrame) {
+    leave_if_user(&frame, "Overflow Exception");
+
     log::error!("Encountered Overflow Exception");
     log::error!("Overflow Exception: {:#?}", frame);
 }
@@ -1,6 +1,8 @@
-use crate::interrupts::{InterruptVector, exceptions, keyboard, timer};
+use crate::interrupts::{InterruptVector, SYSCALL_VECTOR, exceptions, keyboard, serial};
+use crate::process;
 use kernel_core::sync::init::InitData;
 use x86_64::structures::idt::InterruptDescriptorTable;
+use x86_64::{PrivilegeLevel, VirtAddr};
 
 static IDT: InitData<InterruptDescriptorTable> = InitData::uninit();
 
@@ -78,10 +80,21 @@ pub unsafe fn init() {
     idt.x87_floating_point
         .set_handler_fn(exceptions::x87_floating_point_handler);
 
-    idt[InterruptVector::Timer.with_offset()].set_handler_fn(timer::timer_handler);
+    unsafe {
+        idt[InterruptVector::Timer.with_offset()]
+            .set_handler_addr(VirtAddr::new(process::timer_entry as usize as u64));
+    }
 
     idt[InterruptVector::Keyboard.with_offset()]
         .set_handler_fn(keyboard::keyboard_interrupt_handler);
 
+    idt[InterruptVector::Serial.with_offset()].set_handler_fn(serial::serial_interrupt_handler);
+
+    unsafe {
+        idt[SYSCALL_VECTOR]
+            .set_handler_addr(VirtAddr::new(process::syscall_entry as usize as u64))
+            .set_privilege_level(PrivilegeLevel::Ring3);
+    }
+
     unsafe { IDT.init(idt).load() }
 }
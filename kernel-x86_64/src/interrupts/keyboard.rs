@@ -1,4 +1,4 @@
-use crate::interrupts::apic;
+use crate::interrupts::{InterruptVector, apic};
 use kernel_core::api;
 
 use kernel_core::control::input::INPUT;
@@ -19,17 +19,20 @@ static mut KEYBOARD: Keyboard<KeyLayout, ScancodeSet1> = Keyboard::new(
 
 /// Sets the keyboard layout.
 pub fn set_layout(layout: KeyLayout) {
-    // This is safe, because it's never called inside the interrupt handler.
-    unsafe {
+    // This is safe, because it's never called inside the interrupt handler
+    // and the handler can't run while interrupts are disabled.
+    api::without_interrupts(|| unsafe {
         KEYBOARD = Keyboard::new(
             ScancodeSet1::new(),
             layout,
             HandleControl::MapLettersToUnicode,
         );
-    }
+    });
 }
 
 pub extern "x86-interrupt" fn keyboard_interrupt_handler(_stack_frame: InterruptStackFrame) {
+    InterruptVector::Keyboard.count();
+
     // read scancode from I/O port (0x60)
     let scancode = unsafe { api::port().read_u8(0x60) };
 
@@ -38,7 +41,17 @@ pub extern "x86-interrupt" fn keyboard_interrupt_handler(_stack_frame: Interrupt
         if let Ok(Some(event)) = KEYBOARD.add_byte(scancode)
             && let Some(key) = KEYBOARD.process_keyevent(event)
         {
-            INPUT.get().push(key);
+            let modifiers = KEYBOARD.get_modifiers();
+            let alt = modifiers.lalt || modifiers.ralt;
+
+            // Alt+F1 to Alt+F4 => switch virtual console
+            match key {
+                DecodedKey::RawKey(KeyCode::F1) if alt => INPUT.get().request_console(0),
+                DecodedKey::RawKey(KeyCode::F2) if alt => INPUT.get().request_console(1),
+                DecodedKey::RawKey(KeyCode::F3) if alt => INPUT.get().request_console(2),
+                DecodedKey::RawKey(KeyCode::F4) if alt => INPUT.get().request_console(3),
+                key => INPUT.get().push(key),
+            }
         }
     }
 
@@ -47,6 +60,7 @@ pub extern "x86-interrupt" fn keyboard_interrupt_handler(_stack_frame: Interrupt
     }
 }
 
+#[derive(Debug, Clone, Copy)]
 pub enum KeyLayout {
     DVP104Key,
     Dvorak104Key,
@@ -58,6 +72,28 @@ pub enum KeyLayout {
     De105Key,
 }
 
+impl KeyLayout {
+    /// The names of all layouts as used by [KeyLayout::from_name].
+    pub const NAMES: &'static [&'static str] = &[
+        "dvp", "dvorak", "us", "uk", "jis", "azerty", "colemak", "de",
+    ];
+
+    /// Returns the layout with the given name, e.g. `us` or `de`.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "dvp" => Some(KeyLayout::DVP104Key),
+            "dvorak" => Some(KeyLayout::Dvorak104Key),
+            "us" => Some(KeyLayout::Us104Key),
+            "uk" => Some(KeyLayout::Uk105Key),
+            "jis" => Some(KeyLayout::Jis109Key),
+            "azerty" => Some(KeyLayout::Azerty),
+            "colemak" => Some(KeyLayout::Colemak),
+            "de" => Some(KeyLayout::De105Key),
+            _ => None,
+        }
+    }
+}
+
 impl KeyboardLayout for KeyLayout {
     fn map_keycode(
         &self,
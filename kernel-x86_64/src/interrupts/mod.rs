@@ -1,22 +1,49 @@
+use core::sync::atomic::{AtomicU64, Ordering};
+
 pub mod apic;
 pub mod exceptions;
 pub mod idt;
 pub mod keyboard;
+pub mod serial;
 pub mod timer;
 
 pub const INTERRUPT_OFFSET: u8 = 32;
 
+/// The vector user programs issue system calls with.
+pub const SYSCALL_VECTOR: u8 = 0x80;
+
 #[derive(Debug, Clone, Copy)]
 #[repr(u8)]
 pub enum InterruptVector {
     Timer = 0,
     Keyboard = 1,
+    Serial = 4,
     Error = 19,
     Spurious = 31,
 }
 
+/// The number of handled interrupts of each [InterruptVector].
+static COUNTS: [AtomicU64; 32] = [const { AtomicU64::new(0) }; 32];
+
 impl InterruptVector {
+    /// The vectors of the device interrupts, whose handlers count them.
+    pub const COUNTED: [InterruptVector; 3] = [
+        InterruptVector::Timer,
+        InterruptVector::Keyboard,
+        InterruptVector::Serial,
+    ];
+
     pub fn with_offset(self) -> u8 {
         self as u8 + INTERRUPT_OFFSET
     }
+
+    /// Count a handled interrupt of this vector.
+    pub fn count(self) {
+        COUNTS[self as usize].fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Get the number of handled interrupts of this vector since boot.
+    pub fn counter(self) -> u64 {
+        COUNTS[self as usize].load(Ordering::Relaxed)
+    }
 }
@@ -0,0 +1,24 @@
+use crate::interrupts::{InterruptVector, apic};
+use kernel_core::control::input::INPUT;
+use kernel_core::control::serial_input::SerialDecoder;
+use kernel_core::serial;
+use x86_64::structures::idt::InterruptStackFrame;
+
+/// The global serial decoder. It's only ever mutated during interrupts, so it's safe to be `mut`.
+static mut DECODER: SerialDecoder = SerialDecoder::new();
+
+pub extern "x86-interrupt" fn serial_interrupt_handler(_stack_frame: InterruptStackFrame) {
+    InterruptVector::Serial.count();
+
+    // Read all received bytes, since the FIFO may hold more than one
+    while let Some(byte) = serial::read_byte() {
+        // `DECODER` is only ever accessed here, so this is safe.
+        unsafe {
+            DECODER.push(byte, INPUT.get());
+        }
+    }
+
+    unsafe {
+        apic::end_of_interrupt();
+    }
+}
@@ -1,10 +1,26 @@
-use crate::interrupts::apic;
+use crate::interrupts::{InterruptVector, apic};
+use core::sync::atomic::{AtomicU64, Ordering};
 use x86_64::structures::idt::InterruptStackFrame;
 
+static TICKS: AtomicU64 = AtomicU64::new(0);
+
 pub extern "x86-interrupt" fn timer_handler(_: InterruptStackFrame) {
+    tick();
+}
+
+/// Count the tick and signal the end of the interrupt.
+pub fn tick() {
+    TICKS.fetch_add(1, Ordering::Relaxed);
+    InterruptVector::Timer.count();
+
     unsafe {
         // TODO: handle time
 
         apic::end_of_interrupt();
     }
 }
+
+/// Get the number of timer ticks since the APIC timer was started.
+pub fn ticks() -> u64 {
+    TICKS.load(Ordering::Relaxed)
+}
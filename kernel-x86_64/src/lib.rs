@@ -6,7 +6,7 @@
 extern crate alloc;
 
 use kernel_core::api;
-use kernel_core::api::{KernelApi, MemoryApi, PortApi, TimeApi};
+use kernel_core::api::{KernelApi, MemoryApi, PortApi, ProcessApi, TimeApi};
 use kernel_core::info::KernelApiInfo;
 use x86_64::structures::paging::PageTableFlags;
 use x86_64::{PhysAddr, VirtAddr};
@@ -19,6 +19,7 @@ pub mod init;
 pub mod interrupts;
 pub mod memory;
 pub mod port;
+pub mod process;
 pub mod time;
 
 pub const KERNEL_API: KernelApi = KernelApi {
@@ -48,12 +49,23 @@ pub const KERNEL_API: KernelApi = KernelApi {
         realloc: memory::allocator::realloc,
         translate,
         map_to,
+        protect,
     },
     time: TimeApi {
         read_local: time::read_local,
         read_utc: time::read_utc,
         set_offset: time::set_offset,
     },
+    process: ProcessApi {
+        create_space: process::space::create,
+        destroy_space: process::space::destroy,
+        map: process::space::map,
+        read: process::space::read,
+        write: process::space::write,
+        enter: process::enter,
+        ticks: interrupts::timer::ticks,
+        tick_millis: interrupts::apic::MILLIS_PER_TICK as u64,
+    },
 };
 
 fn seed_quality() -> u64 {
@@ -96,3 +108,17 @@ unsafe fn map_to(addr: usize, writable: bool, cache: bool) -> usize {
 
     unsafe { memory::mapper::map_address(PhysAddr::new(addr as u64), flags) }.as_u64() as usize
 }
+
+unsafe fn protect(addr: usize, size: usize, writable: bool, executable: bool) -> bool {
+    let mut flags = PageTableFlags::PRESENT;
+
+    if writable {
+        flags.insert(PageTableFlags::WRITABLE);
+    }
+
+    if !executable {
+        flags.insert(PageTableFlags::NO_EXECUTE);
+    }
+
+    unsafe { memory::mapper::update_range_flags(VirtAddr::new(addr as u64), size, flags) }
+}
@@ -4,7 +4,7 @@ use kernel_core::requests;
 use kernel_core::sync::mutex::Mutex;
 use limine::memory_map::EntryType;
 use x86_64::PhysAddr;
-use x86_64::structures::paging::{FrameAllocator, PhysFrame, Size4KiB};
+use x86_64::structures::paging::{FrameAllocator, FrameDeallocator, PhysFrame, Size4KiB};
 
 /// Global frame allocator
 pub static FRAME_ALLOCATOR: Mutex<PageFrameAllocator> = Mutex::new(PageFrameAllocator::new());
@@ -98,3 +98,9 @@ unsafe impl FrameAllocator<Size4KiB> for PageFrameAllocator {
         unsafe { self.pop_frame() }
     }
 }
+
+impl FrameDeallocator<Size4KiB> for PageFrameAllocator {
+    unsafe fn deallocate_frame(&mut self, frame: PhysFrame<Size4KiB>) {
+        unsafe { self.push_frame(frame, phys_mem_offset()) }
+    }
+}
@@ -145,3 +145,26 @@ pub unsafe fn map_address_range(start: PhysAddr, size: usize, flags: PageTableFl
         })
     })
 }
+
+/// Replaces the page table flags of all pages in the given virtual address range.
+///
+/// Returns `false` if any page of the range is not mapped, pages before it are updated anyway.
+///
+/// # Safety
+/// The range must not be used in a way the new flags forbid afterward.
+pub unsafe fn update_range_flags(start: VirtAddr, size: usize, flags: PageTableFlags) -> bool {
+    MAPPER.get().run_mut(|mapper| {
+        let first = Page::<PageSize>::containing_address(start);
+        let last = Page::<PageSize>::containing_address(start + size.max(1) as u64 - 1);
+
+        Page::range_inclusive(first, last).all(|page| {
+            match unsafe { mapper.update_flags(page, flags) } {
+                Ok(flush) => {
+                    flush.flush();
+                    true
+                }
+                Err(_) => false,
+            }
+        })
+    })
+}
@@ -0,0 +1,261 @@
+use crate::gdt;
+use crate::interrupts::timer;
+use core::arch::naked_asm;
+use core::mem::offset_of;
+use kernel_core::process::{Trap, UserContext};
+use x86_64::registers::control::{Cr0, Cr0Flags, Cr4, Cr4Flags};
+use x86_64::registers::rflags::RFlags;
+
+pub mod space;
+
+const TRAP_SYSCALL: u64 = 0;
+const TRAP_EXCEPTION: u64 = 1;
+const TRAP_TIMER: u64 = 2;
+
+/// The number of general purpose and interrupt frame registers in a [UserContext].
+const FRAME_REGISTERS: usize = 20;
+
+/// The kernel stack pointer saved by [enter_user].
+static mut KERNEL_RSP: u64 = 0;
+/// The kernel page table saved by [enter_user].
+static mut KERNEL_CR3: u64 = 0;
+/// The user context that is currently running.
+static mut CONTEXT: *mut UserContext = core::ptr::null_mut();
+/// The name and address of the last exception caused in user mode.
+static mut EXCEPTION: (&str, usize) = ("", 0);
+
+/// Initialize user mode support.
+///
+/// Enables SSE, since user programs are free to use it.
+///
+/// # Safety
+/// Must only be called once before any user program runs.
+pub unsafe fn init() {
+    unsafe {
+        Cr0::update(|flags| {
+            flags.remove(Cr0Flags::EMULATE_COPROCESSOR);
+            flags.insert(Cr0Flags::MONITOR_COPROCESSOR);
+        });
+
+        Cr4::update(|flags| flags.insert(Cr4Flags::OSFXSR | Cr4Flags::OSXMMEXCPT_ENABLE));
+    }
+}
+
+/// Run the given user context in the given address space until it traps back into the kernel.
+///
+/// # Safety
+/// The address space must be valid and contain the program of the context.
+pub unsafe fn enter(space: usize, context: &mut UserContext) -> Trap {
+    let gdt = gdt::get_gdt();
+
+    // Never trust the selectors or privileged flags of a saved context
+    context.cs = gdt.user_code.0 as u64;
+    context.ss = gdt.user_data.0 as u64;
+    context.rflags = (context.rflags | RFlags::INTERRUPT_FLAG.bits())
+        & !(RFlags::IOPL_LOW | RFlags::IOPL_HIGH).bits();
+
+    match unsafe { enter_user(context, space as u64) } {
+        TRAP_SYSCALL => Trap::Syscall,
+        TRAP_TIMER => Trap::Timer,
+        _ => {
+            let (name, addr) = unsafe { EXCEPTION };
+
+            Trap::Exception { name, addr }
+        }
+    }
+}
+
+/// Leave user mode after the current user program caused an exception.
+///
+/// # Safety
+/// Must only be called from an exception handler that interrupted user mode.
+pub unsafe fn leave_exception(name: &'static str, addr: usize) -> ! {
+    unsafe {
+        EXCEPTION = (name, addr);
+
+        leave_user(core::ptr::null(), TRAP_EXCEPTION)
+    }
+}
+
+/// Save the kernel context, switch to the user address space and `iretq` into the user context.
+///
+/// Returns the trap code once [leave_user] is reached.
+#[unsafe(naked)]
+unsafe extern "sysv64" fn enter_user(context: *mut UserContext, cr3: u64) -> u64 {
+    naked_asm!(
+        // Save the kernel context
+        "push rbp",
+        "push rbx",
+        "push r12",
+        "push r13",
+        "push r14",
+        "push r15",
+        "pushfq",
+        "cli",
+        "mov [rip + {kernel_rsp}], rsp",
+        "mov [rip + {context}], rdi",
+        "mov rax, cr3",
+        "mov [rip + {kernel_cr3}], rax",
+        // Switch to the user address space
+        "mov cr3, rsi",
+        // Restore the user state
+        "fxrstor64 [rdi + {fpu}]",
+        "push qword ptr [rdi + {ss}]",
+        "push qword ptr [rdi + {rsp}]",
+        "push qword ptr [rdi + {rflags}]",
+        "push qword ptr [rdi + {cs}]",
+        "push qword ptr [rdi + {rip}]",
+        "mov r15, [rdi + {r15}]",
+        "mov r14, [rdi + {r14}]",
+        "mov r13, [rdi + {r13}]",
+        "mov r12, [rdi + {r12}]",
+        "mov r11, [rdi + {r11}]",
+        "mov r10, [rdi + {r10}]",
+        "mov r9, [rdi + {r9}]",
+        "mov r8, [rdi + {r8}]",
+        "mov rbp, [rdi + {rbp}]",
+        "mov rsi, [rdi + {rsi}]",
+        "mov rdx, [rdi + {rdx}]",
+        "mov rcx, [rdi + {rcx}]",
+        "mov rbx, [rdi + {rbx}]",
+        "mov rax, [rdi + {rax}]",
+        "mov rdi, [rdi + {rdi}]",
+        "iretq",
+        kernel_rsp = sym KERNEL_RSP,
+        kernel_cr3 = sym KERNEL_CR3,
+        context = sym CONTEXT,
+        fpu = const offset_of!(UserContext, fpu),
+        r15 = const offset_of!(UserContext, r15),
+        r14 = const offset_of!(UserContext, r14),
+        r13 = const offset_of!(UserContext, r13),
+        r12 = const offset_of!(UserContext, r12),
+        r11 = const offset_of!(UserContext, r11),
+        r10 = const offset_of!(UserContext, r10),
+        r9 = const offset_of!(UserContext, r9),
+        r8 = const offset_of!(UserContext, r8),
+        rbp = const offset_of!(UserContext, rbp),
+        rdi = const offset_of!(UserContext, rdi),
+        rsi = const offset_of!(UserContext, rsi),
+        rdx = const offset_of!(UserContext, rdx),
+        rcx = const offset_of!(UserContext, rcx),
+        rbx = const offset_of!(UserContext, rbx),
+        rax = const offset_of!(UserContext, rax),
+        rip = const offset_of!(UserContext, rip),
+        cs = const offset_of!(UserContext, cs),
+        rflags = const offset_of!(UserContext, rflags),
+        rsp = const offset_of!(UserContext, rsp),
+        ss = const offset_of!(UserContext, ss),
+    )
+}
+
+/// Switch back to the kernel address space and return from [enter_user] with the given trap code.
+///
+/// If `frame` is not null, it must point to the saved user registers in [UserContext] layout,
+/// which are copied into the running context together with the FPU state.
+#[unsafe(naked)]
+unsafe extern "sysv64" fn leave_user(frame: *const u64, trap: u64) -> ! {
+    naked_asm!(
+        "mov rax, [rip + {kernel_cr3}]",
+        "mov cr3, rax",
+        "mov rax, rsi",
+        "test rdi, rdi",
+        "jz 2f",
+        // Save the user state
+        "mov rdx, [rip + {context}]",
+        "fxsave64 [rdx + {fpu}]",
+        "mov rsi, rdi",
+        "lea rdi, [rdx + {frame}]",
+        "mov ecx, {frame_registers}",
+        "cld",
+        "rep movsq",
+        // Restore the kernel context
+        "2:",
+        "mov rsp, [rip + {kernel_rsp}]",
+        "popfq",
+        "pop r15",
+        "pop r14",
+        "pop r13",
+        "pop r12",
+        "pop rbx",
+        "pop rbp",
+        "ret",
+        kernel_rsp = sym KERNEL_RSP,
+        kernel_cr3 = sym KERNEL_CR3,
+        context = sym CONTEXT,
+        fpu = const offset_of!(UserContext, fpu),
+        frame = const offset_of!(UserContext, r15),
+        frame_registers = const FRAME_REGISTERS,
+    )
+}
+
+/// The `int 0x80` system call entry.
+///
+/// Pushes the general purpose registers below the interrupt stack frame,
+/// so the stack matches the [UserContext] register layout, and leaves user mode.
+#[unsafe(naked)]
+pub unsafe extern "sysv64" fn syscall_entry() {
+    naked_asm!(
+        "push rax",
+        "push rbx",
+        "push rcx",
+        "push rdx",
+        "push rsi",
+        "push rdi",
+        "push rbp",
+        "push r8",
+        "push r9",
+        "push r10",
+        "push r11",
+        "push r12",
+        "push r13",
+        "push r14",
+        "push r15",
+        "mov rdi, rsp",
+        "mov esi, {trap}",
+        "jmp {leave}",
+        trap = const TRAP_SYSCALL,
+        leave = sym leave_user,
+    )
+}
+
+/// The timer interrupt entry.
+///
+/// Interrupts of kernel code are passed on to the regular [timer::timer_handler].
+/// If user mode was interrupted, the user registers are saved and its time slice ends.
+#[unsafe(naked)]
+pub unsafe extern "sysv64" fn timer_entry() {
+    naked_asm!(
+        // Check the privilege level of the interrupted code segment
+        "test qword ptr [rsp + 8], 3",
+        "jnz 2f",
+        "jmp {handler}",
+        "2:",
+        "push rax",
+        "push rbx",
+        "push rcx",
+        "push rdx",
+        "push rsi",
+        "push rdi",
+        "push rbp",
+        "push r8",
+        "push r9",
+        "push r10",
+        "push r11",
+        "push r12",
+        "push r13",
+        "push r14",
+        "push r15",
+        "call {tick}",
+        "mov rdi, rsp",
+        "mov esi, {trap}",
+        "jmp {leave}",
+        handler = sym timer::timer_handler,
+        tick = sym user_tick,
+        trap = const TRAP_TIMER,
+        leave = sym leave_user,
+    )
+}
+
+extern "sysv64" fn user_tick() {
+    timer::tick();
+}
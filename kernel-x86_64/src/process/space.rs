@@ -0,0 +1,233 @@
+use crate::memory::frame_alloc::FRAME_ALLOCATOR;
+use crate::memory::mapper::{MAPPER, PageSize};
+use crate::memory::phys_mem_offset;
+use x86_64::structures::paging::mapper::{MappedFrame, TranslateResult};
+use x86_64::structures::paging::{
+    FrameAllocator, FrameDeallocator, Mapper, OffsetPageTable, Page, PageTable, PageTableFlags,
+    PhysFrame, Translate,
+};
+use x86_64::{PhysAddr, VirtAddr};
+
+/// The first level 4 entry of the kernel half, which is shared between all address spaces.
+const KERNEL_HALF: usize = 256;
+
+/// The end of the lower, user accessible half of the address space.
+const USER_END: usize = 0x0000_8000_0000_0000;
+
+const PAGE_SIZE: usize = 4096;
+
+/// Create a new user address space.
+///
+/// Returns the physical address of the level 4 page table, or [None] if out of memory.
+pub fn create() -> Option<usize> {
+    let frame = FRAME_ALLOCATOR.run(|alloc| alloc.allocate_frame())?;
+    let table = unsafe { table_mut(frame.start_address()) };
+
+    table.zero();
+
+    // Share the kernel half, so interrupts and system calls work in every address space
+    MAPPER.get().run(|mapper| {
+        let kernel = mapper.level_4_table();
+
+        for (entry, kernel) in table.iter_mut().zip(kernel.iter()).skip(KERNEL_HALF) {
+            *entry = kernel.clone();
+        }
+    });
+
+    Some(frame.start_address().as_u64() as usize)
+}
+
+/// Destroy the given address space, freeing all user pages and page tables.
+///
+/// # Safety
+/// The address space must be valid and must not be active or used afterward.
+pub unsafe fn destroy(space: usize) {
+    unsafe fn free_table(phys: PhysAddr, level: u8) {
+        let table = unsafe { table_mut(phys) };
+
+        for entry in table.iter() {
+            if entry.is_unused() {
+                continue;
+            }
+
+            if level > 1 && !entry.flags().contains(PageTableFlags::HUGE_PAGE) {
+                unsafe { free_table(entry.addr(), level - 1) };
+            } else {
+                FRAME_ALLOCATOR.run(|alloc| unsafe {
+                    alloc.deallocate_frame(PhysFrame::containing_address(entry.addr()))
+                });
+            }
+        }
+
+        FRAME_ALLOCATOR
+            .run(|alloc| unsafe { alloc.deallocate_frame(PhysFrame::containing_address(phys)) });
+    }
+
+    let table = unsafe { table_mut(PhysAddr::new(space as u64)) };
+
+    for entry in table.iter().take(KERNEL_HALF) {
+        if !entry.is_unused() {
+            unsafe { free_table(entry.addr(), 3) };
+        }
+    }
+
+    FRAME_ALLOCATOR.run(|alloc| unsafe {
+        alloc.deallocate_frame(PhysFrame::containing_address(PhysAddr::new(space as u64)))
+    });
+}
+
+/// Map zeroed memory at the given page aligned address into the address space.
+///
+/// Pages that are already mapped keep their contents and gain the requested permissions.
+///
+/// # Safety
+/// The address space must be valid.
+pub unsafe fn map(
+    space: usize,
+    addr: usize,
+    size: usize,
+    writable: bool,
+    executable: bool,
+) -> bool {
+    let Some(end) = addr.checked_add(size).filter(|end| *end <= USER_END) else {
+        return false;
+    };
+
+    let mut flags = PageTableFlags::PRESENT | PageTableFlags::USER_ACCESSIBLE;
+
+    if writable {
+        flags.insert(PageTableFlags::WRITABLE);
+    }
+
+    if !executable {
+        flags.insert(PageTableFlags::NO_EXECUTE);
+    }
+
+    let parent_flags =
+        PageTableFlags::PRESENT | PageTableFlags::WRITABLE | PageTableFlags::USER_ACCESSIBLE;
+
+    let mut mapper = unsafe { mapper(space) };
+
+    FRAME_ALLOCATOR.run(|alloc| {
+        for page_addr in (addr..end).step_by(PAGE_SIZE) {
+            let page = Page::<PageSize>::containing_address(VirtAddr::new(page_addr as u64));
+
+            if let TranslateResult::Mapped {
+                flags: existing, ..
+            } = mapper.translate(page.start_address())
+            {
+                // Merge permissions of segments sharing a page
+                let mut merged = existing | (flags & !PageTableFlags::NO_EXECUTE);
+
+                if existing.contains(PageTableFlags::NO_EXECUTE) && !executable {
+                    merged.insert(PageTableFlags::NO_EXECUTE);
+                } else {
+                    merged.remove(PageTableFlags::NO_EXECUTE);
+                }
+
+                match unsafe { mapper.update_flags(page, merged) } {
+                    Ok(flush) => flush.ignore(),
+                    Err(_) => return false,
+                }
+
+                continue;
+            }
+
+            let Some(frame) = alloc.allocate_frame() else {
+                return false;
+            };
+
+            unsafe {
+                table_mut(frame.start_address()).zero();
+
+                match mapper.map_to_with_table_flags(page, frame, flags, parent_flags, alloc) {
+                    Ok(flush) => flush.ignore(),
+                    Err(_) => return false,
+                }
+            }
+        }
+
+        true
+    })
+}
+
+/// Read user memory of the address space into `buf`.
+///
+/// # Safety
+/// The address space must be valid.
+pub unsafe fn read(space: usize, addr: usize, buf: &mut [u8]) -> bool {
+    unsafe {
+        copy(space, addr, buf.len(), |offset, ptr, len| {
+            core::ptr::copy_nonoverlapping(ptr, buf[offset..].as_mut_ptr(), len)
+        })
+    }
+}
+
+/// Write `data` into user memory of the address space.
+///
+/// # Safety
+/// The address space must be valid.
+pub unsafe fn write(space: usize, addr: usize, data: &[u8]) -> bool {
+    unsafe {
+        copy(space, addr, data.len(), |offset, ptr, len| {
+            core::ptr::copy_nonoverlapping(data[offset..].as_ptr(), ptr, len)
+        })
+    }
+}
+
+/// Walk the user memory range page by page and call `f` with the offset into the range,
+/// a kernel pointer to the memory and the length of the chunk.
+///
+/// Returns false if any part of the range is not mapped user memory.
+unsafe fn copy(
+    space: usize,
+    addr: usize,
+    len: usize,
+    mut f: impl FnMut(usize, *mut u8, usize),
+) -> bool {
+    if addr.checked_add(len).is_none_or(|end| end > USER_END) {
+        return false;
+    }
+
+    let mapper = unsafe { mapper(space) };
+    let mut offset = 0;
+
+    while offset < len {
+        let current = addr + offset;
+        let chunk = (PAGE_SIZE - current % PAGE_SIZE).min(len - offset);
+
+        let TranslateResult::Mapped {
+            frame: MappedFrame::Size4KiB(frame),
+            offset: frame_offset,
+            flags,
+        } = mapper.translate(VirtAddr::new(current as u64))
+        else {
+            return false;
+        };
+
+        if !flags.contains(PageTableFlags::USER_ACCESSIBLE) {
+            return false;
+        }
+
+        let phys = frame.start_address().as_u64() + frame_offset;
+
+        f(offset, (phys + phys_mem_offset()) as *mut u8, chunk);
+
+        offset += chunk;
+    }
+
+    true
+}
+
+unsafe fn mapper(space: usize) -> OffsetPageTable<'static> {
+    unsafe {
+        OffsetPageTable::new(
+            table_mut(PhysAddr::new(space as u64)),
+            VirtAddr::new(phys_mem_offset()),
+        )
+    }
+}
+
+unsafe fn table_mut<'a>(phys: PhysAddr) -> &'a mut PageTable {
+    unsafe { &mut *((phys.as_u64() + phys_mem_offset()) as *mut PageTable) }
+}
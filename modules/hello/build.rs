@@ -0,0 +1,7 @@
+fn main() {
+    let arch = std::env::var("CARGO_CFG_TARGET_ARCH").unwrap();
+    let sdk = concat!(env!("CARGO_MANIFEST_DIR"), "/../../kernel-module-sdk");
+
+    println!("cargo:rustc-link-arg=-T{sdk}/linker-{arch}-module.ld");
+    println!("cargo:rerun-if-changed={sdk}/linker-{arch}-module.ld");
+}
@@ -0,0 +1,49 @@
+//! Example kernel module, which registers a `hello` command.
+
+#![no_std]
+
+extern crate alloc;
+
+use alloc::string::String;
+use kernel_module_sdk::ModuleContext;
+use kernel_module_sdk::kernel_core::control::command::args::{Arg, ArgType, Args};
+use kernel_module_sdk::kernel_core::control::command::{Command, CommandIo};
+use kernel_module_sdk::log;
+
+kernel_module_sdk::declare_module! {
+    name: "hello",
+    version: env!("CARGO_PKG_VERSION"),
+    author: "Subatomic OS",
+    description: "Example module, which registers a 'hello' command.",
+    init: init,
+    update: update,
+    exit: exit,
+}
+
+fn init(context: &mut ModuleContext) -> Result<(), &'static str> {
+    let registered = context.register_command(
+        Command::new("hello", "Greets the given name or the world.", hello).args(&[
+            Arg::positional("name", "The name to greet.", ArgType::String).default("world"),
+        ]),
+    );
+
+    if !registered {
+        return Err("command 'hello' is already registered");
+    }
+
+    log::info!("Hello from the hello module!");
+
+    Ok(())
+}
+
+fn update() {}
+
+fn exit() {
+    log::info!("Goodbye from the hello module!");
+}
+
+fn hello(args: &Args, io: &mut CommandIo) -> Result<(), String> {
+    writeln!(io, "Hello, {}!", args.str("name").unwrap_or("world"));
+
+    Ok(())
+}
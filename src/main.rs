@@ -5,11 +5,12 @@
 
 extern crate alloc;
 
+use kernel_core::config::BOOT_CONFIG;
 use kernel_core::control::CONTROL;
 use kernel_core::control::display::{DISPLAY, Display};
 use kernel_core::info::KernelInfo;
 use kernel_core::requests::BASE_REVISION;
-use kernel_core::{api, control, logger, module};
+use kernel_core::{api, config, control, logger, module, process};
 use log::LevelFilter;
 
 pub mod allocator;
@@ -19,6 +20,7 @@ pub mod panic;
 unsafe extern "C" fn kernel_main() -> ! {
     unsafe {
         init(
+            // Overridden by the `log` option on the kernel command line
             option_env!("LOG_LEVEL")
                 .unwrap_or("info")
                 .parse()
@@ -45,6 +47,7 @@ unsafe extern "C" fn kernel_main() -> ! {
     loop {
         CONTROL.get().update();
         module::run_update();
+        process::run_update();
         api::halt();
     }
 }
@@ -67,6 +70,11 @@ unsafe fn init(filter: LevelFilter) {
         (kernel.init)();
     }
 
+    log::info!("Parsing kernel command line...");
+    unsafe {
+        config::init();
+    }
+
     log::info!("Loading kernel modules...");
     unsafe {
         module::init();
@@ -87,10 +95,20 @@ unsafe fn init(filter: LevelFilter) {
         (kernel.setup)();
     }
 
+    if let Some(zone) = BOOT_CONFIG.get().tz {
+        log::info!("Setting time zone to {zone:?}...");
+
+        let (hours, minutes, seconds) = zone.to_offset();
+        api::time().set_offset(hours, minutes, seconds);
+    }
+
     log::info!("Initializing kernel modules...");
     module::run_init();
 
     api::enable_interrupts();
+
+    control::script::profile();
+    control::script::autorun();
 }
 
 fn print_intro() {